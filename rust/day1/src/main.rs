@@ -1,7 +1,25 @@
-use std::fs;
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+};
 
 fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("--annotate") => annotate(parse_annotate_style(args.next().as_deref())),
+        Some("--stream") => stream(args.next().as_deref().unwrap_or("input")).unwrap(),
+        _ => solve(),
+    }
+}
+
+fn solve() {
     let input = get_input();
+
     let simple_digits = get_pairs(&input, parse_slice_simple);
     let simple_sum: usize = simple_digits.sum();
 
@@ -12,41 +30,254 @@ fn main() {
     println!("Sum of values 2: {}", complex_sum);
 }
 
+fn annotate(style: AnnotateStyle) {
+    let input = get_input();
+
+    for (name, parse_fn) in [("simple", parse_slice_simple as ParseFn), ("complex", parse_slice_complex)] {
+        println!("== {name} ==");
+        for line in input.lines() {
+            println!("{}", annotate_line(line, parse_fn, style));
+        }
+    }
+}
+
+fn stream(path: &str) -> io::Result<()> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    let simple_sum = sum_reader(BufReader::new(fs::File::open(path)?), parse_slice_simple, threads, CHUNK_LINES)?;
+    println!("Sum of values: {}", simple_sum);
+
+    let complex_sum = sum_reader(BufReader::new(fs::File::open(path)?), parse_slice_complex, threads, CHUNK_LINES)?;
+    println!("Sum of values 2: {}", complex_sum);
+    Ok(())
+}
+
 pub fn get_input() -> String {
     String::from_utf8(fs::read("input").unwrap()).unwrap()
 }
 
-pub fn get_pairs<'a>(input: &'a str, parse_fn: fn(&str) -> Option<usize>) -> impl Iterator<Item = usize> + 'a {
+/// A digit matched at the start of a slice: its value and how many bytes it spans.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Token {
+    pub value: usize,
+    pub len: usize,
+}
+
+pub type ParseFn = fn(&str) -> Option<Token>;
+
+pub fn get_pairs<'a>(input: &'a str, parse_fn: ParseFn) -> impl Iterator<Item = usize> + 'a {
     input
         .lines()
-        .map(move |line| {
-            let mut digits = (0..line.len()).map(|i| &line[i..]).flat_map(parse_fn);
+        .map(move |line| line_value(line, parse_fn).expect("Always at least 1 digit in input line"))
+}
+
+pub fn line_value(line: &str, parse_fn: ParseFn) -> Option<usize> {
+    let mut digits = get_tokens(line, parse_fn).map(|(_, token)| token.value);
 
-            let first = digits
-                .next()
-                .expect("Always at least 1 digit in input line");
-            let last = digits.last().unwrap_or(first);
-            first * 10 + last
-        })
+    let first = digits.next()?;
+    let last = digits.last().unwrap_or(first);
+    Some(first * 10 + last)
 }
 
-fn parse_slice_simple(slice: &str) -> Option<usize> {
-    slice[0..1].parse().ok()
+const CHUNK_LINES: usize = 64 * 1024;
+
+struct Chunk {
+    first_line: usize,
+    lines: Vec<String>,
+}
+
+/// Sums the calibration values of every line in `reader` across `threads` workers.
+///
+/// Lines are handed out in chunks of `chunk_lines` over a channel that holds at most one chunk per
+/// worker, so memory stays bounded by `threads * chunk_lines` lines however large the input is.
+/// Reading stops as soon as a worker finds a line without digits.
+pub fn sum_reader<R: BufRead>(mut reader: R, parse_fn: ParseFn, threads: usize, chunk_lines: usize) -> io::Result<usize> {
+    let threads = threads.max(1);
+    let chunk_lines = chunk_lines.max(1);
+    let (sender, receiver) = mpsc::sync_channel::<Chunk>(threads);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let failed = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                let failed = &failed;
+                // A worker stops at its first line without digits and returns its number.
+                scope.spawn(move || -> Result<usize, usize> {
+                    let mut sum = 0;
+                    loop {
+                        let chunk = match receiver.lock().unwrap().recv() {
+                            Ok(chunk) => chunk,
+                            Err(_) => return Ok(sum),
+                        };
+                        for (i, line) in chunk.lines.iter().enumerate() {
+                            let Some(value) = line_value(line, parse_fn) else {
+                                failed.store(true, Ordering::Relaxed);
+                                return Err(chunk.first_line + i + 1);
+                            };
+                            sum += value;
+                        }
+                    }
+                })
+            })
+            .collect();
+        // Only the workers hold the receiver now, so sending fails once they have all returned.
+        drop(receiver);
+
+        let read_result = read_chunks(&mut reader, chunk_lines, |chunk| {
+            !failed.load(Ordering::Relaxed) && sender.send(chunk).is_ok()
+        });
+        drop(sender);
+
+        let results: Vec<_> = workers.into_iter().map(|worker| worker.join().unwrap()).collect();
+        read_result?;
+
+        // Chunks go out in order, so the earliest bad line any worker found is the first overall.
+        match results.iter().filter_map(|result| result.err()).min() {
+            Some(line) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {line} has no digits"),
+            )),
+            None => Ok(results.into_iter().flatten().sum()),
+        }
+    })
+}
+
+/// Reads `reader` line by line into chunks, stopping early once `send` reports the workers are gone.
+fn read_chunks<R: BufRead>(reader: &mut R, chunk_lines: usize, mut send: impl FnMut(Chunk) -> bool) -> io::Result<()> {
+    let mut first_line = 0;
+    let mut lines = Vec::with_capacity(chunk_lines);
+    let mut buffer = String::new();
+
+    while reader.read_line(&mut buffer)? != 0 {
+        let line = buffer.trim_end_matches(['\n', '\r']);
+        lines.push(line.to_string());
+        buffer.clear();
+
+        if lines.len() == chunk_lines {
+            let chunk = Chunk { first_line, lines: std::mem::replace(&mut lines, Vec::with_capacity(chunk_lines)) };
+            first_line += chunk_lines;
+            if !send(chunk) {
+                return Ok(());
+            }
+        }
+    }
+
+    if !lines.is_empty() {
+        send(Chunk { first_line, lines });
+    }
+    Ok(())
+}
+
+/// Every token found in `line`, paired with the byte offset it starts at.
+pub fn get_tokens(line: &str, parse_fn: ParseFn) -> impl Iterator<Item = (usize, Token)> + '_ {
+    line.char_indices()
+        .filter_map(move |(i, _)| parse_fn(&line[i..]).map(|token| (i, token)))
+}
+
+fn parse_slice_simple(slice: &str) -> Option<Token> {
+    let value = slice.chars().next()?.to_digit(10)?;
+    Some(Token { value: value as usize, len: 1 })
 }
 
 static DIGITS: [&str; 9] = [
     "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
 ];
-fn parse_slice_complex(slice: &str) -> Option<usize> {
-    slice[0..1].parse().ok().or_else(|| {
+fn parse_slice_complex(slice: &str) -> Option<Token> {
+    parse_slice_simple(slice).or_else(|| {
         DIGITS
             .iter()
             .enumerate()
             .find(|(_, &digit)| slice.starts_with(digit))
-            .map(|(i, _)| i + 1)
+            .map(|(i, digit)| Token { value: i + 1, len: digit.len() })
     })
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AnnotateStyle {
+    Ansi,
+    Brackets,
+}
+
+fn parse_annotate_style(arg: Option<&str>) -> AnnotateStyle {
+    match arg {
+        Some("brackets") => AnnotateStyle::Brackets,
+        _ => AnnotateStyle::Ansi,
+    }
+}
+
+static ANSI_FIRST: &str = "\x1b[32m";
+static ANSI_LAST: &str = "\x1b[34m";
+static ANSI_BOTH: &str = "\x1b[33m";
+static ANSI_RESET: &str = "\x1b[0m";
+
+/// Renders `line` with the first matched token marked `[..]` (green) and the last `{..}` (blue),
+/// followed by the calibration value. Overlapping tokens such as `twone` interleave the markers
+/// (`[tw{o]ne}`) or show the shared characters in yellow.
+pub fn annotate_line(line: &str, parse_fn: ParseFn, style: AnnotateStyle) -> String {
+    let mut tokens = get_tokens(line, parse_fn);
+    let Some(first) = tokens.next() else {
+        return format!("{line} => no digits");
+    };
+    let last = tokens.last().unwrap_or(first);
+
+    let span = |(start, token): (usize, Token)| start..start + token.len;
+    let (first_span, last_span) = (span(first), span(last));
+
+    let mut output = String::new();
+    let mut colour = None;
+    for (i, ch) in line.char_indices() {
+        match style {
+            AnnotateStyle::Brackets => {
+                if i == first_span.end {
+                    output.push(']');
+                }
+                if i == last_span.end && last_span != first_span {
+                    output.push('}');
+                }
+                if i == first_span.start {
+                    output.push('[');
+                }
+                if i == last_span.start && last_span != first_span {
+                    output.push('{');
+                }
+            }
+            AnnotateStyle::Ansi => {
+                let next = match (first_span.contains(&i), last_span.contains(&i)) {
+                    (true, true) if first_span != last_span => Some(ANSI_BOTH),
+                    (true, _) => Some(ANSI_FIRST),
+                    (false, true) => Some(ANSI_LAST),
+                    (false, false) => None,
+                };
+                if next != colour {
+                    output.push_str(next.unwrap_or(ANSI_RESET));
+                    colour = next;
+                }
+            }
+        }
+        output.push(ch);
+    }
+
+    match style {
+        AnnotateStyle::Brackets => {
+            if line.len() == first_span.end {
+                output.push(']');
+            }
+            if line.len() == last_span.end && last_span != first_span {
+                output.push('}');
+            }
+        }
+        AnnotateStyle::Ansi => {
+            if colour.is_some() {
+                output.push_str(ANSI_RESET);
+            }
+        }
+    }
+
+    format!("{output} => {}", first.1.value * 10 + last.1.value)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -79,4 +310,95 @@ zoneight234
         let expected = vec![29, 83, 13, 24, 42, 14, 76];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn sum_reader_test() {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four\r
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+        let result: Vec<_> = [(1, 1), (2, 3), (4, 100)]
+            .iter()
+            .map(|&(threads, chunk_lines)| sum_reader(input.as_bytes(), parse_slice_complex, threads, chunk_lines).unwrap())
+            .collect();
+
+        let expected = vec![281, 281, 281];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn sum_reader_missing_digit_test() {
+        let input = "1abc2\npqr3stu8vwx\nabcdef\ntreb7uchet";
+
+        let result = sum_reader(input.as_bytes(), parse_slice_simple, 2, 1).unwrap_err();
+
+        assert_eq!(result.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(result.to_string(), "line 3 has no digits");
+    }
+
+    #[test]
+    pub fn sum_reader_first_error_test() {
+        let input = "1\n2\nabc\n3\ndef\né1\n";
+
+        let result: Vec<_> = [(1, 1), (3, 1), (2, 2)]
+            .iter()
+            .map(|&(threads, chunk_lines)| {
+                sum_reader(input.as_bytes(), parse_slice_simple, threads, chunk_lines).unwrap_err().to_string()
+            })
+            .collect();
+
+        assert!(result.iter().all(|error| error == "line 3 has no digits"));
+        assert_eq!(sum_reader("é1\n".as_bytes(), parse_slice_simple, 1, 1).unwrap(), 11);
+    }
+
+    #[test]
+    pub fn sum_reader_stops_after_error_test() {
+        let input = format!("abc\n{}", "1x1\n".repeat(10_000));
+
+        let result = sum_reader(input.as_bytes(), parse_slice_simple, 1, 1).unwrap_err();
+
+        assert_eq!(result.to_string(), "line 1 has no digits");
+    }
+
+    #[test]
+    pub fn annotate_line_brackets_test() {
+        let inputs = ["1abc2", "treb7uchet", "xtwone3four", "twone", "abc"];
+
+        let simple: Vec<_> = inputs
+            .iter()
+            .map(|line| annotate_line(line, parse_slice_simple, AnnotateStyle::Brackets))
+            .collect();
+        let complex: Vec<_> = inputs
+            .iter()
+            .map(|line| annotate_line(line, parse_slice_complex, AnnotateStyle::Brackets))
+            .collect();
+
+        assert_eq!(simple, vec![
+            "[1]abc{2} => 12",
+            "treb[7]uchet => 77",
+            "xtwone[3]four => 33",
+            "twone => no digits",
+            "abc => no digits",
+        ]);
+        assert_eq!(complex, vec![
+            "[1]abc{2} => 12",
+            "treb[7]uchet => 77",
+            "x[two]ne3{four} => 24",
+            "[tw{o]ne} => 21",
+            "abc => no digits",
+        ]);
+    }
+
+    #[test]
+    pub fn annotate_line_ansi_test() {
+        let result = annotate_line("twone", parse_slice_complex, AnnotateStyle::Ansi);
+
+        let expected = "\x1b[32mtw\x1b[33mo\x1b[34mne\x1b[0m => 21";
+        assert_eq!(result, expected);
+    }
 }
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use crate::{Cubes, Game, Round};
+
+/// What the draws of a game say about the bag they were taken from.
+///
+/// Each round is modelled as drawing its cubes without replacement from the bag, with the cubes
+/// put back before the next round, so a round's likelihood is multivariate hypergeometric.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Inference {
+    /// The most likely bag. Ties keep the bag with the fewest cubes of the earliest colours.
+    ///
+    /// Bigger bags in the same proportions are usually more likely, so unless `max` is tight this
+    /// sits on the edge of the search space and `posterior_mean` is the more useful estimate.
+    pub maximum_likelihood: Cubes,
+    pub log_likelihood: f64,
+    /// Expected count of each colour under a uniform prior over the search space.
+    pub posterior_mean: BTreeMap<String, f64>,
+    /// Posterior probability of each count `0..=max`, per colour.
+    pub marginals: BTreeMap<String, Vec<f64>>,
+}
+
+impl Game {
+    /// Infers the bag from this game's rounds, searching every bag with `0..=max` cubes of each of
+    /// `colours`. Returns `None` if no bag in that space could have produced the game.
+    pub fn infer_bag(&self, colours: &[&str], max: usize) -> Option<Inference> {
+        infer_bag(&self.rounds, colours, max)
+    }
+}
+
+/// Like `Game::infer_bag`, but over rounds from any number of games drawn from the same bag.
+pub fn infer_bag(rounds: &[Round], colours: &[&str], max: usize) -> Option<Inference> {
+    let observations = rounds
+        .iter()
+        .map(|round| observe(round, colours))
+        .collect::<Option<Vec<_>>>()?;
+    let ln_factorials = ln_factorials(max * colours.len());
+    let log_likelihood = |bag: &[usize]| {
+        observations
+            .iter()
+            .map(|observed| round_log_likelihood(&ln_factorials, bag, observed))
+            .sum::<f64>()
+    };
+
+    let mut best: Option<(f64, Vec<usize>)> = None;
+    for_each_bag(colours.len(), max, |bag| {
+        let ll = log_likelihood(bag);
+        if ll > f64::NEG_INFINITY && best.as_ref().is_none_or(|(best, _)| ll > *best) {
+            best = Some((ll, bag.to_vec()));
+        }
+    });
+    let (best_ll, best_bag) = best?;
+
+    let mut total = 0.0;
+    let mut marginals = vec![vec![0.0; max + 1]; colours.len()];
+    for_each_bag(colours.len(), max, |bag| {
+        let weight = (log_likelihood(bag) - best_ll).exp();
+        total += weight;
+        for (marginal, &count) in marginals.iter_mut().zip(bag) {
+            marginal[count] += weight;
+        }
+    });
+    for probability in marginals.iter_mut().flatten() {
+        *probability /= total;
+    }
+
+    Some(Inference {
+        maximum_likelihood: colours.iter().copied().zip(best_bag).collect(),
+        log_likelihood: best_ll,
+        posterior_mean: colours
+            .iter()
+            .zip(&marginals)
+            .map(|(colour, marginal)| {
+                let mean = marginal.iter().enumerate().map(|(count, p)| count as f64 * p).sum();
+                (colour.to_string(), mean)
+            })
+            .collect(),
+        marginals: colours
+            .iter()
+            .map(|colour| colour.to_string())
+            .zip(marginals)
+            .collect(),
+    })
+}
+
+/// Cubes of each colour drawn in `round`, or `None` if it draws a colour outside `colours`.
+fn observe(round: &Round, colours: &[&str]) -> Option<Vec<usize>> {
+    round.draws.iter().try_fold(vec![0; colours.len()], |mut observed, draw| {
+        let i = colours.iter().position(|colour| *colour == draw.colour)?;
+        observed[i] += draw.count;
+        Some(observed)
+    })
+}
+
+fn round_log_likelihood(ln_factorials: &[f64], bag: &[usize], observed: &[usize]) -> f64 {
+    if bag.iter().zip(observed).any(|(have, drawn)| drawn > have) {
+        return f64::NEG_INFINITY;
+    }
+
+    let ln_choose = |n: usize, k: usize| ln_factorials[n] - ln_factorials[k] - ln_factorials[n - k];
+    let chosen: f64 = bag.iter().zip(observed).map(|(&have, &drawn)| ln_choose(have, drawn)).sum();
+    chosen - ln_choose(bag.iter().sum(), observed.iter().sum())
+}
+
+fn ln_factorials(max: usize) -> Vec<f64> {
+    (0..=max)
+        .scan(0.0, |acc, n| {
+            if n > 0 {
+                *acc += (n as f64).ln();
+            }
+            Some(*acc)
+        })
+        .collect()
+}
+
+/// Calls `f` with every bag of `colours` counts in `0..=max`, last colour changing fastest.
+fn for_each_bag(colours: usize, max: usize, mut f: impl FnMut(&[usize])) {
+    let mut bag = vec![0; colours];
+    loop {
+        f(&bag);
+
+        let Some(i) = bag.iter().rposition(|&count| count < max) else {
+            return;
+        };
+        bag[i] += 1;
+        bag[i + 1..].fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(result: f64, expected: f64) {
+        assert!((result - expected).abs() < 1e-9, "{result} != {expected}");
+    }
+
+    #[test]
+    pub fn infer_bag_test() {
+        let game: Game = "Game 1: 1 red".parse().unwrap();
+
+        let result = game.infer_bag(&["red", "blue"], 2).unwrap();
+
+        let expected: Cubes = [("red", 1), ("blue", 0)].into_iter().collect();
+        assert_eq!(result.maximum_likelihood, expected);
+        assert_close(result.log_likelihood, 0.0);
+        assert_close(result.posterior_mean["red"], (1.0 + 0.5 + 1.0 / 3.0 + 2.0 * (1.0 + 2.0 / 3.0 + 0.5)) / 4.0);
+        assert_close(result.posterior_mean["blue"], (0.5 + 2.0 / 3.0 + 2.0 * (1.0 / 3.0 + 0.5)) / 4.0);
+        assert_close(result.marginals["red"][0], 0.0);
+    }
+
+    #[test]
+    pub fn infer_bag_rounds_test() {
+        let game: Game = "Game 1: 2 red, 1 blue; 3 blue; 1 red, 1 green".parse().unwrap();
+
+        let result = game.infer_bag(&["red", "green", "blue"], 12).unwrap();
+
+        let expected: Cubes = [("red", 9), ("green", 3), ("blue", 12)].into_iter().collect();
+        assert_eq!(result.maximum_likelihood, expected);
+        assert!(result.posterior_mean["red"] > result.posterior_mean["green"]);
+        assert!(result.posterior_mean["blue"] > result.posterior_mean["red"]);
+        for marginal in result.marginals.values() {
+            assert_close(marginal.iter().sum(), 1.0);
+        }
+    }
+
+    #[test]
+    pub fn infer_bag_impossible_test() {
+        let inputs = ["Game 1: 5 red", "Game 1: 1 purple"];
+
+        let result: Vec<_> = inputs
+            .iter()
+            .map(|input| input.parse::<Game>().unwrap().infer_bag(&["red", "blue"], 4))
+            .collect();
+
+        assert_eq!(result, vec![None, None]);
+    }
+}
@@ -1,120 +1,555 @@
-use std::fs;
+use std::{collections::BTreeMap, env, fmt, fs, process, str::FromStr};
+
+mod inference;
+mod query;
+mod validate;
 
 fn main() {
-    let input = get_input();
+    let Args { bag, path, query, check } = parse_args(env::args().skip(1)).unwrap();
+    let input = get_input(&path);
+
+    if check {
+        let anomalies = validate::check(&input);
+        for anomaly in &anomalies {
+            println!("{anomaly}");
+        }
+        if !anomalies.is_empty() {
+            process::exit(1);
+        }
+    }
+
+    let games = parse_games(&input).unwrap();
+
+    match query {
+        Some(Query::MinimalBag) => {
+            println!("Minimal bag: {}", query::minimal_bag(&games));
+            return;
+        }
+        Some(Query::Cover(k)) => {
+            match query::minimal_bag_covering(&games, k) {
+                Some(cover) => println!("Minimal bag covering {k} games: {} (games {:?})", cover.cubes, cover.games),
+                None => println!("There are fewer than {k} games"),
+            }
+            return;
+        }
+        Some(Query::Infer { game, max }) => {
+            let Some(game) = games.iter().find(|g| g.id == game) else {
+                eprintln!("game {game} not found");
+                process::exit(1);
+            };
+            let colours: Vec<&str> = bag.cubes.iter().map(|(colour, _)| colour).collect();
+            match game.infer_bag(&colours, max) {
+                Some(inference) => {
+                    println!("Maximum likelihood bag: {}", inference.maximum_likelihood);
+                    for (colour, mean) in inference.posterior_mean {
+                        println!("Posterior mean {colour}: {mean:.2}");
+                    }
+                }
+                None => println!("No bag with at most {max} cubes of each colour fits game {}", game.id),
+            }
+            return;
+        }
+        Some(Query::Violations) => {
+            for violation in bag.violations(&games).unwrap() {
+                println!("{violation}");
+            }
+            return;
+        }
+        None => {}
+    }
 
-    let part_1_result: usize = valid_game_ids(&input).sum();
+    let part_1_result: usize = valid_game_ids(&games, &bag).sum::<Result<_, _>>().unwrap();
     println!("Part 1 result: {}", part_1_result);
 
-    let part_2_result: usize = game_powers(&input).sum();
+    let part_2_result: usize = game_powers(&games, &bag).sum::<Result<_, _>>().unwrap();
     println!("Part 2 result: {}", part_2_result);
 }
 
-pub fn get_input() -> String {
-    String::from_utf8(fs::read("input").unwrap()).unwrap()
+pub fn get_input(path: &str) -> String {
+    String::from_utf8(fs::read(path).unwrap()).unwrap()
 }
 
-static RED: usize = 12;
-static GREEN: usize = 13;
-static BLUE: usize = 14;
+enum Query {
+    MinimalBag,
+    Cover(usize),
+    Infer { game: usize, max: usize },
+    Violations,
+}
 
-pub fn valid_game_ids<'a>(input: &'a str) -> impl Iterator<Item = usize> + 'a {
-    input.lines()
-        .filter_map(|line| {
-            let mut split = line.split(':');
-            let id = parse_id(split.next().unwrap());
-            if is_rounds_valid(split.next().unwrap()) {
-                Some(id)
-            } else {
-                None
+struct Args {
+    bag: Bag,
+    path: String,
+    query: Option<Query>,
+    check: bool,
+}
+
+/// `[--bag red=12,green=13,blue=14] [--unknown-colours reject|ignore] [--check]
+/// [--minimal-bag | --cover <k> | --infer <game> [--max <n>] | --violations] [input]`
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut bag = Bag::default();
+    let mut path = "input".to_string();
+    let mut query = None;
+    let mut check = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--minimal-bag" => query = Some(Query::MinimalBag),
+            "--cover" => {
+                let value = args.next().ok_or("--cover needs a value")?;
+                query = Some(Query::Cover(value.parse().map_err(|_| format!("invalid game count `{value}`"))?));
             }
-        })
+            "--infer" => {
+                let value = args.next().ok_or("--infer needs a game id")?;
+                let game = value.parse().map_err(|_| format!("invalid game id `{value}`"))?;
+                query = Some(Query::Infer { game, max: 20 });
+            }
+            "--max" => {
+                let value = args.next().ok_or("--max needs a value")?;
+                let value = value.parse().map_err(|_| format!("invalid cube count `{value}`"))?;
+                match &mut query {
+                    Some(Query::Infer { max, .. }) => *max = value,
+                    _ => return Err("--max only applies to --infer".to_string()),
+                }
+            }
+            "--violations" => query = Some(Query::Violations),
+            "--bag" => {
+                let value = args.next().ok_or("--bag needs a value")?;
+                bag.cubes = value.parse().map_err(|err: ParseGameError| err.to_string())?;
+            }
+            "--unknown-colours" => {
+                let value = args.next().ok_or("--unknown-colours needs a value")?;
+                bag.unknown_colours = value.parse()?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown argument `{arg}`")),
+            _ => path = arg,
+        }
+    }
+    Ok(Args { bag, path, query, check })
 }
 
-#[derive(Default)]
-struct Game {
-    pub red: usize,
-    pub green: usize,
-    pub blue: usize,
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseGameError> {
+    input.lines().map(str::parse).collect()
 }
 
-pub fn game_powers<'a>(input: &'a str) -> impl Iterator<Item = usize> + 'a {
-    input.lines().map(|line| {
-        let game = line.rsplit(':')
-            .next()
-            .unwrap()
-            .split(';')
-            .fold(Game::default(), |mut game, input| {
-                for round in input.split(',') {
-                    let (amount, color) = parse_round(round.trim());
-                    match color {
-                        "red" => game.red = game.red.max(amount),
-                        "green" => game.green = game.green.max(amount),
-                        "blue" => game.blue = game.blue.max(amount),
-                        _ => {}
-                    }
-                }
-                game
-            });
-        game.red * game.green * game.blue
+pub fn valid_game_ids<'a>(games: &'a [Game], bag: &'a Bag) -> impl Iterator<Item = Result<usize, BagError>> + 'a {
+    games.iter().filter_map(|game| match bag.is_possible(game) {
+        Ok(true) => Some(Ok(game.id)),
+        Ok(false) => None,
+        Err(err) => Some(Err(err)),
     })
 }
 
-fn parse_id(input: &str) -> usize {
-    input.rsplit(' ').next().unwrap().parse().unwrap()
+pub fn game_powers<'a>(games: &'a [Game], bag: &'a Bag) -> impl Iterator<Item = Result<usize, BagError>> + 'a {
+    games.iter().map(|game| bag.power(game))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Draw {
+    pub count: usize,
+    pub colour: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Round {
+    pub draws: Vec<Draw>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Game {
+    pub id: usize,
+    pub rounds: Vec<Round>,
 }
 
-fn is_rounds_valid(input: &str) -> bool {
-    input.split(';').all(|round| {
-        round.split(',').all(|round| {
-            let round = round.trim();
-            let mut info = round.split(' ');
-            let amount: usize = info.next().unwrap().parse().unwrap();
-            match info.next().unwrap() {
-                "red" => amount <= RED,
-                "green" => amount <= GREEN,
-                "blue" => amount <= BLUE,
-                _ => false,
+/// A count of cubes per colour. Colours that are not present count as zero.
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct Cubes(BTreeMap<String, usize>);
+
+impl Cubes {
+    pub fn get(&self, colour: &str) -> usize {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn contains(&self, colour: &str) -> bool {
+        self.0.contains_key(colour)
+    }
+
+    pub fn insert(&mut self, colour: impl Into<String>, count: usize) {
+        self.0.insert(colour.into(), count);
+    }
+
+    /// Raises the count of `colour` to at least `count`.
+    pub fn raise(&mut self, colour: &str, count: usize) {
+        match self.0.get_mut(colour) {
+            Some(amount) => *amount = (*amount).max(count),
+            None => self.insert(colour, count),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.0.iter().map(|(colour, count)| (colour.as_str(), *count))
+    }
+
+    pub fn power(&self) -> usize {
+        self.0.values().product()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, usize)> for Cubes {
+    fn from_iter<I: IntoIterator<Item = (S, usize)>>(iter: I) -> Self {
+        Cubes(iter.into_iter().map(|(colour, count)| (colour.into(), count)).collect())
+    }
+}
+
+/// Parses `red=12,green=13,blue=14`.
+impl FromStr for Cubes {
+    type Err = ParseGameError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .split(',')
+            .map(|entry| {
+                let (colour, count) = entry
+                    .split_once('=')
+                    .ok_or_else(|| ParseGameError::MissingColour(entry.to_string()))?;
+                let count = count
+                    .trim()
+                    .parse()
+                    .map_err(|_| ParseGameError::InvalidCount(count.to_string()))?;
+                Ok((colour.trim(), count))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Cubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (colour, count)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
             }
+            write!(f, "{colour}={count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What to do with a draw whose colour the bag does not list.
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum UnknownColours {
+    /// Report the draw as a `BagError`.
+    #[default]
+    Reject,
+    /// Leave the draw out of every calculation.
+    Ignore,
+}
+
+impl FromStr for UnknownColours {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "reject" => Ok(UnknownColours::Reject),
+            "ignore" => Ok(UnknownColours::Ignore),
+            _ => Err(format!("unknown colour policy `{input}`, expected `reject` or `ignore`")),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Bag {
+    pub cubes: Cubes,
+    pub unknown_colours: UnknownColours,
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Bag {
+            cubes: [("red", 12), ("green", 13), ("blue", 14)].into_iter().collect(),
+            unknown_colours: UnknownColours::default(),
+        }
+    }
+}
+
+impl Bag {
+    pub fn new(cubes: Cubes) -> Self {
+        Bag {
+            cubes,
+            unknown_colours: UnknownColours::default(),
+        }
+    }
+
+    /// The draws of `game` this bag knows about, as decided by `unknown_colours`.
+    pub fn draws<'a>(&'a self, game: &'a Game) -> impl Iterator<Item = Result<&'a Draw, BagError>> + 'a {
+        game.draws().filter_map(|draw| self.known_draw(game, draw))
+    }
+
+    fn known_draw<'a>(&self, game: &Game, draw: &'a Draw) -> Option<Result<&'a Draw, BagError>> {
+        if self.cubes.contains(&draw.colour) {
+            return Some(Ok(draw));
+        }
+        match self.unknown_colours {
+            UnknownColours::Reject => Some(Err(BagError::UnknownColour {
+                game: game.id,
+                colour: draw.colour.clone(),
+            })),
+            UnknownColours::Ignore => None,
+        }
+    }
+
+    pub fn is_possible(&self, game: &Game) -> Result<bool, BagError> {
+        self.draws(game).try_fold(true, |possible, draw| {
+            let draw = draw?;
+            Ok(possible && draw.count <= self.cubes.get(&draw.colour))
         })
-    })
+    }
+
+    /// The fewest cubes of each of the bag's colours that `game` could have been played with.
+    pub fn minimum_cubes(&self, game: &Game) -> Result<Cubes, BagError> {
+        let cubes = self.cubes.iter().map(|(colour, _)| (colour, 0)).collect();
+        self.draws(game).try_fold(cubes, |mut cubes: Cubes, draw| {
+            let draw = draw?;
+            cubes.raise(&draw.colour, draw.count);
+            Ok(cubes)
+        })
+    }
+
+    pub fn power(&self, game: &Game) -> Result<usize, BagError> {
+        self.minimum_cubes(game).map(|cubes| cubes.power())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BagError {
+    UnknownColour { game: usize, colour: String },
+}
+
+impl fmt::Display for BagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownColour { game, colour } => {
+                write!(f, "game {game} draws `{colour}`, which is not in the bag")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BagError {}
+
+impl Game {
+    pub fn draws(&self) -> impl Iterator<Item = &Draw> {
+        self.rounds.iter().flat_map(|round| round.draws.iter())
+    }
+
+    /// The fewest cubes of every colour drawn in this game.
+    pub fn minimum_cubes(&self) -> Cubes {
+        self.draws().fold(Cubes::default(), |mut cubes, draw| {
+            cubes.raise(&draw.colour, draw.count);
+            cubes
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseGameError {
+    MissingColon,
+    InvalidId(String),
+    InvalidCount(String),
+    MissingColour(String),
 }
 
-fn parse_round(input: &str) -> (usize, &str) {
-    let mut info = input.split(' ');
-    let amount: usize = info.next().unwrap().parse().unwrap();
-    (amount, info.next().unwrap())
+impl fmt::Display for ParseGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColon => write!(f, "expected `Game <id>: <rounds>`"),
+            Self::InvalidId(id) => write!(f, "invalid game id `{id}`"),
+            Self::InvalidCount(count) => write!(f, "invalid cube count `{count}`"),
+            Self::MissingColour(draw) => write!(f, "missing colour in draw `{draw}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGameError {}
+
+impl FromStr for Draw {
+    type Err = ParseGameError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let (count, colour) = input
+            .split_once(' ')
+            .ok_or_else(|| ParseGameError::MissingColour(input.to_string()))?;
+        let count = count
+            .parse()
+            .map_err(|_| ParseGameError::InvalidCount(count.to_string()))?;
+        let colour = colour.trim();
+        Ok(Draw {
+            count,
+            colour: colour.to_string(),
+        })
+    }
+}
+
+impl FromStr for Round {
+    type Err = ParseGameError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let draws = input
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Round { draws })
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseGameError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (id, rounds) = input.split_once(':').ok_or(ParseGameError::MissingColon)?;
+        let id = parse_id(id)?;
+        let rounds = rounds
+            .split(';')
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Game { id, rounds })
+    }
+}
+
+fn parse_id(input: &str) -> Result<usize, ParseGameError> {
+    input
+        .strip_prefix("Game ")
+        .and_then(|id| id.trim().parse().ok())
+        .ok_or_else(|| ParseGameError::InvalidId(input.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{valid_game_ids, game_powers};
+    use crate::*;
 
-    #[test]
-    pub fn valid_game_ids_test() {
-       let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    pub static DEFAULT_INPUT: &str = "
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-       let result: Vec<usize> = valid_game_ids(input).collect();
+    #[test]
+    pub fn valid_game_ids_test() {
+        let games = parse_games(DEFAULT_INPUT.trim()).unwrap();
+
+        let result: Vec<usize> = valid_game_ids(&games, &Bag::default()).collect::<Result<_, _>>().unwrap();
 
-       let expected = vec![1, 2, 5];
-       assert_eq!(result, expected);
+        let expected = vec![1, 2, 5];
+        assert_eq!(result, expected);
     }
 
     #[test]
     pub fn game_powers_test() {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let games = parse_games(DEFAULT_INPUT.trim()).unwrap();
 
-        let result: Vec<usize> = game_powers(input).collect();
+        let result: Vec<usize> = game_powers(&games, &Bag::default()).collect::<Result<_, _>>().unwrap();
 
         let expected = vec![48, 12, 1560, 630, 36];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn game_from_str_test() {
+        let result: Game = "Game 12: 3 blue, 4 red; 2 green".parse().unwrap();
+
+        let expected = Game {
+            id: 12,
+            rounds: vec![
+                Round {
+                    draws: vec![
+                        Draw { count: 3, colour: "blue".to_string() },
+                        Draw { count: 4, colour: "red".to_string() },
+                    ],
+                },
+                Round {
+                    draws: vec![Draw { count: 2, colour: "green".to_string() }],
+                },
+            ],
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn game_from_str_error_test() {
+        let inputs = [
+            "Game 1 3 blue",
+            "Round 1: 3 blue",
+            "Game 1: x blue",
+            "Game 1: 3",
+        ];
+
+        let result: Vec<_> = inputs
+            .iter()
+            .map(|input| input.parse::<Game>().unwrap_err())
+            .collect();
+
+        let expected = vec![
+            ParseGameError::MissingColon,
+            ParseGameError::InvalidId("Round 1".to_string()),
+            ParseGameError::InvalidCount("x".to_string()),
+            ParseGameError::MissingColour("3".to_string()),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn cubes_from_str_test() {
+        let result: Cubes = "red=12, green=13,blue=14".parse().unwrap();
+
+        let expected: Cubes = [("red", 12), ("green", 13), ("blue", 14)].into_iter().collect();
+        assert_eq!(result, expected);
+        assert_eq!(result.to_string(), "blue=14,green=13,red=12");
+    }
+
+    #[test]
+    pub fn custom_bag_test() {
+        let input = "
+Game 1: 3 cyan, 4 red; 1 magenta, 2 green, 6 blue; 2 yellow
+Game 2: 9 cyan, 2 green; 3 green, 4 blue, 1 red
+Game 3: 8 green, 6 blue, 2 red; 5 blue";
+        let games = parse_games(input.trim()).unwrap();
+        let bag = Bag::new("red=5,green=5,blue=6,cyan=4,magenta=1,yellow=2".parse().unwrap());
+
+        let ids: Vec<usize> = valid_game_ids(&games, &bag).collect::<Result<_, _>>().unwrap();
+        let powers: Vec<usize> = game_powers(&games, &bag).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(ids, vec![1]);
+        assert_eq!(powers, vec![288, 0, 0]);
+    }
+
+    #[test]
+    pub fn unknown_colours_test() {
+        let games = parse_games("Game 1: 3 blue, 4 red; 2 purple\nGame 2: 1 red, 1 green, 1 blue").unwrap();
+        let mut bag = Bag::default();
+
+        let rejected: Vec<_> = valid_game_ids(&games, &bag).collect();
+        let rejected_powers: Vec<_> = game_powers(&games, &bag).collect();
+
+        let error = BagError::UnknownColour { game: 1, colour: "purple".to_string() };
+        assert_eq!(rejected, vec![Err(error.clone()), Ok(2)]);
+        assert_eq!(rejected_powers, vec![Err(error), Ok(1)]);
+
+        bag.unknown_colours = UnknownColours::Ignore;
+        let ignored: Vec<_> = valid_game_ids(&games, &bag).collect();
+        let ignored_powers: Vec<_> = game_powers(&games, &bag).collect();
+
+        assert_eq!(ignored, vec![Ok(1), Ok(2)]);
+        assert_eq!(ignored_powers, vec![Ok(0), Ok(1)]);
+    }
+
+    #[test]
+    pub fn parse_args_test() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+        let result = args(&["--check", "games.txt"]).unwrap();
+
+        assert_eq!(result.path, "games.txt");
+        assert!(result.check);
+        assert_eq!(args(&["--chek"]).err(), Some("unknown argument `--chek`".to_string()));
+    }
 }
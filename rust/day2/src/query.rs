@@ -0,0 +1,225 @@
+use std::fmt;
+
+use crate::{Bag, BagError, Cubes, Game};
+
+/// The smallest bag that makes every game possible.
+pub fn minimal_bag(games: &[Game]) -> Cubes {
+    games.iter().fold(Cubes::default(), |mut bag, game| {
+        for (colour, count) in game.minimum_cubes().iter() {
+            bag.raise(colour, count);
+        }
+        bag
+    })
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Cover {
+    pub cubes: Cubes,
+    /// Ids of every game the bag makes possible, which may be more than were asked for.
+    pub games: Vec<usize>,
+}
+
+/// The bag with the fewest cubes in total that makes at least `k` games possible, or `None` if
+/// there are fewer than `k` games.
+///
+/// Every colour's count only ever needs to be zero or one of the per-game minimums, so this is an
+/// exact branch and bound over those values, one colour at a time. Ties keep the first bag found.
+pub fn minimal_bag_covering(games: &[Game], k: usize) -> Option<Cover> {
+    if k > games.len() {
+        return None;
+    }
+
+    let needs: Vec<Cubes> = games.iter().map(Game::minimum_cubes).collect();
+    let colours: Vec<String> = minimal_bag(games)
+        .iter()
+        .map(|(colour, _)| colour.to_string())
+        .collect();
+
+    let mut search = CoverSearch {
+        needs: &needs,
+        colours: &colours,
+        k,
+        counts: Vec::with_capacity(colours.len()),
+        best: None,
+    };
+    search.run(0, (0..games.len()).collect(), 0);
+
+    let (_, counts, covered) = search.best?;
+    Some(Cover {
+        cubes: colours.iter().cloned().zip(counts).collect(),
+        games: covered.iter().map(|&i| games[i].id).collect(),
+    })
+}
+
+struct CoverSearch<'a> {
+    needs: &'a [Cubes],
+    colours: &'a [String],
+    k: usize,
+    counts: Vec<usize>,
+    best: Option<(usize, Vec<usize>, Vec<usize>)>,
+}
+
+impl CoverSearch<'_> {
+    fn run(&mut self, colour: usize, candidates: Vec<usize>, total: usize) {
+        let Some(name) = self.colours.get(colour) else {
+            if self.best.as_ref().is_none_or(|(best, _, _)| total < *best) {
+                self.best = Some((total, self.counts.clone(), candidates));
+            }
+            return;
+        };
+
+        let mut values: Vec<usize> = candidates.iter().map(|&i| self.needs[i].get(name)).collect();
+        values.push(0);
+        values.sort_unstable();
+        values.dedup();
+
+        for value in values {
+            if self.best.as_ref().is_some_and(|(best, _, _)| total + value >= *best) {
+                break;
+            }
+
+            let remaining: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&i| self.needs[i].get(name) <= value)
+                .collect();
+            if remaining.len() < self.k {
+                continue;
+            }
+
+            let bound = total + value + self.lower_bound(colour + 1, &remaining);
+            if self.best.as_ref().is_some_and(|(best, _, _)| bound >= *best) {
+                continue;
+            }
+
+            self.counts.push(value);
+            self.run(colour + 1, remaining, total + value);
+            self.counts.pop();
+        }
+    }
+
+    /// Each later colour needs at least its `k`-th smallest requirement among `candidates`.
+    fn lower_bound(&self, colour: usize, candidates: &[usize]) -> usize {
+        if self.k == 0 {
+            return 0;
+        }
+
+        self.colours[colour..]
+            .iter()
+            .map(|name| {
+                let mut values: Vec<usize> = candidates.iter().map(|&i| self.needs[i].get(name)).collect();
+                values.sort_unstable();
+                values[self.k - 1]
+            })
+            .sum()
+    }
+}
+
+/// A draw that took more cubes of a colour than the bag holds.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Violation {
+    pub game: usize,
+    /// Index of the round within the game, starting at 0.
+    pub round: usize,
+    pub colour: String,
+    pub count: usize,
+    pub available: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "game {}, round {}: drew {} {} but the bag holds {}",
+            self.game,
+            self.round + 1,
+            self.count,
+            self.colour,
+            self.available
+        )
+    }
+}
+
+impl Bag {
+    /// Every draw in `games` that this bag could not have produced.
+    pub fn violations(&self, games: &[Game]) -> Result<Vec<Violation>, BagError> {
+        let mut violations = vec![];
+        for game in games {
+            for (round, draws) in game.rounds.iter().enumerate() {
+                for draw in draws.draws.iter().filter_map(|draw| self.known_draw(game, draw)) {
+                    let draw = draw?;
+                    let available = self.cubes.get(&draw.colour);
+                    if draw.count > available {
+                        violations.push(Violation {
+                            game: game.id,
+                            round,
+                            colour: draw.colour.clone(),
+                            count: draw.count,
+                            available,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_games, tests::DEFAULT_INPUT};
+
+    #[test]
+    pub fn minimal_bag_test() {
+        let games = parse_games(DEFAULT_INPUT.trim()).unwrap();
+
+        let result = minimal_bag(&games);
+
+        let expected: Cubes = [("red", 20), ("green", 13), ("blue", 15)].into_iter().collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn minimal_bag_covering_test() {
+        let games = parse_games(DEFAULT_INPUT.trim()).unwrap();
+
+        let result: Vec<_> = (0..=6).map(|k| minimal_bag_covering(&games, k)).collect();
+
+        let cover = |red, green, blue, games: Vec<usize>| {
+            Some(Cover {
+                cubes: [("red", red), ("green", green), ("blue", blue)].into_iter().collect(),
+                games,
+            })
+        };
+        let expected = vec![
+            cover(0, 0, 0, vec![]),
+            cover(1, 3, 4, vec![2]),
+            cover(6, 3, 4, vec![2, 5]),
+            cover(6, 3, 6, vec![1, 2, 5]),
+            cover(14, 3, 15, vec![1, 2, 4, 5]),
+            cover(20, 13, 15, vec![1, 2, 3, 4, 5]),
+            None,
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn violations_test() {
+        let games = parse_games(DEFAULT_INPUT.trim()).unwrap();
+
+        let result: Vec<_> = Bag::default()
+            .violations(&games)
+            .unwrap()
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+
+        let expected = vec![
+            "game 3, round 1: drew 20 red but the bag holds 12",
+            "game 4, round 3: drew 15 blue but the bag holds 14",
+            "game 4, round 3: drew 14 red but the bag holds 12",
+        ];
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{Game, ParseGameError};
+
+/// Something in the input that parses but is probably a mistake, or that does not parse at all.
+///
+/// `line` and `round` are indices starting at 0; `Display` shows them starting at 1.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Anomaly {
+    Unparseable { line: usize, error: ParseGameError },
+    DuplicateGameId { line: usize, game: usize, first_line: usize },
+    DuplicateColour { line: usize, game: usize, round: usize, colour: String },
+    ZeroCount { line: usize, game: usize, round: usize, colour: String },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unparseable { line, error } => write!(f, "line {}: {error}", line + 1),
+            Self::DuplicateGameId { line, game, first_line } => {
+                write!(f, "line {}: game {game} was already listed on line {}", line + 1, first_line + 1)
+            }
+            Self::DuplicateColour { line, game, round, colour } => write!(
+                f,
+                "line {}: game {game}, round {} mentions {colour} more than once",
+                line + 1,
+                round + 1
+            ),
+            Self::ZeroCount { line, game, round, colour } => write!(
+                f,
+                "line {}: game {game}, round {} draws 0 {colour}",
+                line + 1,
+                round + 1
+            ),
+        }
+    }
+}
+
+/// Parses every line of `input` and reports both parse failures and anomalies in the games.
+pub fn check(input: &str) -> Vec<Anomaly> {
+    let mut anomalies = vec![];
+    let mut games = vec![];
+    for (line, text) in input.lines().enumerate() {
+        match text.parse::<Game>() {
+            Ok(game) => games.push((line, game)),
+            Err(error) => anomalies.push(Anomaly::Unparseable { line, error }),
+        }
+    }
+
+    anomalies.extend(find_anomalies(&games));
+    anomalies.sort_by_key(|anomaly| match anomaly {
+        Anomaly::Unparseable { line, .. }
+        | Anomaly::DuplicateGameId { line, .. }
+        | Anomaly::DuplicateColour { line, .. }
+        | Anomaly::ZeroCount { line, .. } => *line,
+    });
+    anomalies
+}
+
+fn find_anomalies(games: &[(usize, Game)]) -> Vec<Anomaly> {
+    let mut anomalies = vec![];
+    let mut seen_ids = HashMap::new();
+    for &(line, ref game) in games {
+        if let Some(&first_line) = seen_ids.get(&game.id) {
+            anomalies.push(Anomaly::DuplicateGameId {
+                line,
+                game: game.id,
+                first_line,
+            });
+        } else {
+            seen_ids.insert(game.id, line);
+        }
+
+        for (round, draws) in game.rounds.iter().enumerate() {
+            let mut seen_colours = vec![];
+            for draw in &draws.draws {
+                if draw.count == 0 {
+                    anomalies.push(Anomaly::ZeroCount {
+                        line,
+                        game: game.id,
+                        round,
+                        colour: draw.colour.clone(),
+                    });
+                }
+
+                if seen_colours.contains(&&draw.colour) {
+                    anomalies.push(Anomaly::DuplicateColour {
+                        line,
+                        game: game.id,
+                        round,
+                        colour: draw.colour.clone(),
+                    });
+                } else {
+                    seen_colours.push(&draw.colour);
+                }
+            }
+        }
+    }
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn check_test() {
+        let input = "
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green, 1 blue; 0 green
+Game 3 8 green
+Game 1: 5 red, 0 red";
+
+        let result = check(input.trim());
+
+        let expected = vec![
+            Anomaly::DuplicateColour { line: 1, game: 2, round: 0, colour: "blue".to_string() },
+            Anomaly::ZeroCount { line: 1, game: 2, round: 1, colour: "green".to_string() },
+            Anomaly::Unparseable { line: 2, error: ParseGameError::MissingColon },
+            Anomaly::DuplicateGameId { line: 3, game: 1, first_line: 0 },
+            Anomaly::ZeroCount { line: 3, game: 1, round: 0, colour: "red".to_string() },
+            Anomaly::DuplicateColour { line: 3, game: 1, round: 0, colour: "red".to_string() },
+        ];
+        assert_eq!(result, expected);
+        assert_eq!(result[1].to_string(), "line 2: game 2, round 2 draws 0 green");
+    }
+
+    #[test]
+    pub fn check_clean_test() {
+        let input = "
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue";
+
+        let result = check(input.trim());
+
+        assert_eq!(result, vec![]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+use crate::schematic::{Schematic, Symbol};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    pub fn apply(&self, values: &[usize]) -> usize {
+        match self {
+            Aggregation::Product => values.iter().product(),
+            Aggregation::Sum => values.iter().sum(),
+            Aggregation::Max => values.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+/// Which symbols are gears and how a gear's part numbers combine into its ratio.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub parts: RangeInclusive<usize>,
+    pub aggregation: Aggregation,
+}
+
+impl Default for GearRule {
+    /// A `*` touching exactly two part numbers, whose ratio is their product.
+    fn default() -> Self {
+        Self {
+            symbols: vec!['*'],
+            parts: 2..=2,
+            aggregation: Aggregation::Product,
+        }
+    }
+}
+
+impl GearRule {
+    /// Every symbol that is a gear under this rule, with the values of its part numbers.
+    pub fn gears<'a>(&'a self, schematic: &'a Schematic) -> impl Iterator<Item = (&'a Symbol, Vec<usize>)> + 'a {
+        schematic
+            .symbol_values()
+            .filter(|(symbol, parts)| self.symbols.contains(&symbol.ch) && self.parts.contains(&parts.len()))
+    }
+
+    /// The sum of every gear's ratio.
+    pub fn evaluate(&self, schematic: &Schematic) -> usize {
+        self.gears(schematic)
+            .map(|(_, parts)| self.aggregation.apply(&parts))
+            .sum()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ParseGearRuleError(String);
+
+impl fmt::Display for ParseGearRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid gear rule `{}`, expected `<symbols>:<min>[-<max>]:<product|sum|max>`", self.0)
+    }
+}
+
+impl std::error::Error for ParseGearRuleError {}
+
+/// Parses `<symbols>:<min>[-<max>]:<product|sum|max>`, for example `*:2:product` or `*#:2-3:sum`.
+impl FromStr for GearRule {
+    type Err = ParseGearRuleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || ParseGearRuleError(input.to_string());
+
+        let mut fields = input.rsplitn(3, ':');
+        let aggregation = match fields.next().ok_or_else(error)? {
+            "product" => Aggregation::Product,
+            "sum" => Aggregation::Sum,
+            "max" => Aggregation::Max,
+            _ => return Err(error()),
+        };
+        let parts = fields.next().ok_or_else(error)?;
+        let (min, max) = parts.split_once('-').unwrap_or((parts, parts));
+        let min = min.parse().map_err(|_| error())?;
+        let max = max.parse().map_err(|_| error())?;
+        let symbols: Vec<char> = fields.next().ok_or_else(error)?.chars().collect();
+        if symbols.is_empty() {
+            return Err(error());
+        }
+
+        Ok(Self {
+            symbols,
+            parts: min..=max,
+            aggregation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::Grid, parse_schematic, tests::DEFAULT_INPUT};
+
+    #[test]
+    pub fn gear_rule_evaluate_test() {
+        let grid: Grid<char> = DEFAULT_INPUT.trim().parse().unwrap();
+        let schematic = parse_schematic(&grid);
+
+        let rules = ["*:2:product", "*:1-2:sum", "*$#:1:max", "+:2:product"];
+        let result: Vec<_> = rules
+            .iter()
+            .map(|rule| rule.parse::<GearRule>().unwrap().evaluate(&schematic))
+            .collect();
+
+        let expected = vec![467835, 467 + 35 + 617 + 755 + 598, 617 + 664 + 633, 0];
+        assert_eq!(result, expected);
+        assert_eq!(GearRule::default().evaluate(&schematic), 467835);
+    }
+
+    #[test]
+    pub fn gear_rule_from_str_test() {
+        let result: GearRule = "*:2-4:max".parse().unwrap();
+
+        let expected = GearRule {
+            symbols: vec!['*'],
+            parts: 2..=4,
+            aggregation: Aggregation::Max,
+        };
+        assert_eq!(result, expected);
+        assert!("*:2".parse::<GearRule>().is_err());
+        assert!(":2:sum".parse::<GearRule>().is_err());
+        assert!("*:x:sum".parse::<GearRule>().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use std::{fmt, ops::Index, str::FromStr};
+
+pub type Position = (usize, usize);
+
+static NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+static NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1), (0, 1),
+    (1, -1), (1, 0), (1, 1),
+];
+
+/// A rectangular grid addressed by `(row, col)`, stored row by row.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from `rows`, which must all be the same length.
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Result<Self, ParseGridError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for row in rows {
+            let start = cells.len();
+            cells.extend(row);
+            let row_width = cells.len() - start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(ParseGridError::Ragged {
+                        row: height,
+                        width: row_width,
+                        expected: width,
+                    })
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// Builds a grid from `rows`, padding short rows at the end with `fill`.
+    pub fn from_rows_padded<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let mut rows: Vec<Vec<T>> = rows.into_iter().map(|row| row.into_iter().collect()).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, fill.clone());
+        }
+        Self::from_rows(rows).expect("padded rows are all the same width")
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Position) -> Option<&T> {
+        if row < self.height && col < self.width {
+            self.cells.get(row * self.width + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| ((i / self.width, i % self.width), cell))
+    }
+
+    /// The up to 4 orthogonal neighbours of `position` that are inside the grid.
+    pub fn neighbours_4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offsets(position, &NEIGHBOURS_4)
+    }
+
+    /// The up to 8 orthogonal and diagonal neighbours of `position` that are inside the grid,
+    /// row by row.
+    pub fn neighbours_8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.offsets(position, &NEIGHBOURS_8)
+    }
+
+    fn offsets<'a>(&'a self, (row, col): Position, offsets: &'a [(isize, isize)]) -> impl Iterator<Item = Position> + 'a {
+        offsets.iter().filter_map(move |&(d_row, d_col)| {
+            let row = row.checked_add_signed(d_row).filter(|&row| row < self.height)?;
+            let col = col.checked_add_signed(d_col).filter(|&col| col < self.width)?;
+            Some((row, col))
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position).expect("position should be inside the grid")
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseGridError {
+    Ragged { row: usize, width: usize, expected: usize },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ragged { row, width, expected } => {
+                write!(f, "row {} is {width} wide, expected {expected}", row + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+impl Grid<char> {
+    /// Like `from_str`, but pads short lines at the end with `fill` instead of failing.
+    pub fn parse_padded(input: &str, fill: char) -> Self {
+        Grid::from_rows_padded(text_lines(input).map(str::chars), fill)
+    }
+}
+
+/// Parses one row per line. Every line must have the same number of chars.
+impl FromStr for Grid<char> {
+    type Err = ParseGridError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Grid::from_rows(text_lines(input).map(str::chars))
+    }
+}
+
+/// Splits `input` into lines ending in `\n`, `\r\n` or a lone `\r`, ignoring a leading byte order
+/// mark and a trailing line ending.
+pub fn text_lines(input: &str) -> impl Iterator<Item = &str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let input = input
+        .strip_suffix("\r\n")
+        .or_else(|| input.strip_suffix(['\n', '\r']))
+        .unwrap_or(input);
+
+    let mut rest = (!input.is_empty()).then_some(input);
+    std::iter::from_fn(move || {
+        let line = rest?;
+        match line.find(['\n', '\r']) {
+            Some(end) => {
+                let next = if line[end..].starts_with("\r\n") { end + 2 } else { end + 1 };
+                rest = Some(&line[next..]);
+                Some(&line[..end])
+            }
+            None => {
+                rest = None;
+                Some(line)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn grid_from_str_test() {
+        let grid: Grid<char> = "ab\r\ncd\r\nef".parse().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&['a', 'b'][..], &['c', 'd'], &['e', 'f']]);
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!("ab\ncde".parse::<Grid<char>>(), Err(ParseGridError::Ragged { row: 1, width: 3, expected: 2 }));
+    }
+
+    #[test]
+    pub fn text_lines_test() {
+        let inputs = ["a\nb\r\nc\rd\n", "\u{feff}a\r\n\r\nb\r\n", "", "a"];
+
+        let result: Vec<Vec<_>> = inputs.iter().map(|input| text_lines(input).collect()).collect();
+
+        let expected = vec![vec!["a", "b", "c", "d"], vec!["a", "", "b"], vec![], vec!["a"]];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn grid_parse_padded_test() {
+        let grid = Grid::parse_padded("1€2\r\n3\r\n", '.');
+
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+
+        assert_eq!(rows, vec!["1€2", "3.."]);
+        assert_eq!(grid[(0, 2)], '2');
+    }
+
+    #[test]
+    pub fn grid_neighbours_test() {
+        let grid: Grid<char> = "abc\ndef\nghi".parse().unwrap();
+
+        let corner: Vec<_> = grid.neighbours_8((0, 0)).collect();
+        let centre: Vec<_> = grid.neighbours_4((1, 1)).collect();
+        let edge: Vec<_> = grid.neighbours_8((1, 2)).map(|position| grid[position]).collect();
+
+        assert_eq!(corner, vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(centre, vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+        assert_eq!(edge, vec!['b', 'c', 'e', 'h', 'i']);
+    }
+}
@@ -1,28 +1,83 @@
-use std::fs;
+use std::{env, fs, process};
+
+use gear::GearRule;
+use grid::{Grid, ParseGridError};
+use schematic::{Number, Schematic, Symbol};
+
+mod gear;
+mod grid;
+mod render;
+mod schematic;
 
 fn main() {
+    let args = parse_args(env::args().skip(1)).unwrap();
     let input = get_input();
 
-    let schematic = parse_schematic(&input);
+    if let Err(error) = run(&input, args) {
+        eprintln!("{error}, try --pad-ragged");
+        process::exit(1);
+    }
+}
+
+fn run(input: &str, Args { gear_rule, render, output, pad_ragged }: Args) -> Result<(), ParseGridError> {
+    let grid = if pad_ragged {
+        Grid::parse_padded(input, '.')
+    } else {
+        input.parse()?
+    };
+
+    let schematic = parse_schematic(&grid);
+
+    if let Some(style) = render {
+        let rendered = render::render(&grid, &schematic, &gear_rule, style);
+        match output {
+            Some(path) => fs::write(path, rendered).unwrap(),
+            None => print!("{rendered}"),
+        }
+        return Ok(());
+    }
 
     let part_1: usize = schematic
-        .iter()
-        .flat_map(|(_, parts)| parts)
+        .part_numbers()
+        .map(|number| number.value)
         .sum();
     println!("Part 1 result: {}", part_1);
 
-    let part_2: usize = schematic
-        .iter()
-        .filter_map(|(i, parts)| match &input[*i..i+1] {
-            "*" => if parts.len() == 2 {
-                Some(parts[0] * parts[1])
-            } else {
-                None
-            },
-            _=> None,
-        })
-        .sum();
+    let part_2 = gear_rule.evaluate(&schematic);
     println!("Part 2 result: {}", part_2);
+    Ok(())
+}
+
+struct Args {
+    gear_rule: GearRule,
+    render: Option<render::Style>,
+    output: Option<String>,
+    pad_ragged: bool,
+}
+
+/// `[--gear <symbols>:<min>[-<max>]:<product|sum|max>] [--render <ansi|html> [--output <path>]]
+/// [--pad-ragged]`
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut gear_rule = GearRule::default();
+    let mut render = None;
+    let mut output = None;
+    let mut pad_ragged = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--pad-ragged" => pad_ragged = true,
+            "--render" => {
+                let value = args.next().ok_or("--render needs a value")?;
+                render = Some(value.parse()?);
+            }
+            "--output" => output = Some(args.next().ok_or("--output needs a path")?),
+            "--gear" => {
+                let value = args.next().ok_or("--gear needs a value")?;
+                gear_rule = value.parse().map_err(|err: gear::ParseGearRuleError| err.to_string())?;
+            }
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    Ok(Args { gear_rule, render, output, pad_ragged })
 }
 
 
@@ -30,83 +85,62 @@ pub fn get_input() -> String {
     String::from_utf8(fs::read("input").unwrap()).unwrap()
 }
 
-pub fn parse_schematic(input: &str) -> Vec<(usize, Vec<usize>)> {
-    let width = input.find('\n').expect("end of line") + 1;
-    let check_max = input.len();
+pub fn parse_schematic(grid: &Grid<char>) -> Schematic {
+    let numbers = parse_numbers(grid);
+    let symbols: Vec<_> = grid
+        .iter()
+        .filter(|(_, ch)| is_symbol(ch))
+        .map(|(position, &ch)| Symbol { position, ch })
+        .collect();
 
-    input
-        .char_indices()
-        .filter_map(move |(i, ch)| {
-            if !is_symbol(&ch) || ch == '.' {
-                return None;
-            }
+    let mut number_at: Vec<Vec<Option<usize>>> = grid.rows().map(|cells| vec![None; cells.len()]).collect();
+    for (i, number) in numbers.iter().enumerate() {
+        number_at[number.row][number.cols.clone()].fill(Some(i));
+    }
+    let number_at = Grid::from_rows(number_at).expect("rows of a grid are all the same width");
 
-            let top_left = i.saturating_sub(width + 1);
-            let top_right = i.saturating_sub(width) + 2;
-            let bottom_left = i + width - 1;
-            let bottom_right = i + width + 2;
-
-            let check_ranges = [
-                (top_left..top_right),
-                (i.saturating_sub(1)..check_max.min(i + 2)),
-                (check_max.min(bottom_left)..check_max.min(bottom_right)),
-            ];
-            let iter = check_ranges
-                .iter()
-                .flat_map(|range| {
-                    input[range.start..range.end]
-                        .char_indices()
-                        .scan(false, |state, (i, ch)| {
-                            Some(
-                                if *state {
-                                    *state = ch.is_digit(10);
-                                    None
-                                } else {
-                                    *state = ch.is_digit(10);
-                                    if *state {
-                                        Some(i)
-                                    } else {
-                                        None
-                                    }
-                                }
-                            )
-                        })
-                        .filter_map(|i| match i {
-                            Some(i) => Some(parse_number(input, range.start + i)),
-                            None => None,
-                        })
-                });
-            Some((i, iter.collect()))
-        })
-        .collect()
+    let mut schematic = Schematic::new(numbers, symbols);
+    for i in 0..schematic.symbols.len() {
+        for neighbour in grid.neighbours_8(schematic.symbols[i].position) {
+            if let Some(number) = number_at[neighbour] {
+                schematic.connect(i, number);
+            }
+        }
+    }
+    schematic
 }
 
-fn parse_number(input: &str, index: usize) -> usize {
-    let (start_i, _) = input[..index + 1]
-        .char_indices()
-        .rev()
-        .take_while(|(_, ch)| ch.is_digit(10))
-        .last()
-        .unwrap();
-
-    input[start_i..input.len()]
-        .chars()
-        .map_while(|ch| ch.to_digit(10))
-        .fold(0, |acc, digit| acc * 10 + digit as usize)
+fn parse_numbers(grid: &Grid<char>) -> Vec<Number> {
+    let mut numbers = vec![];
+    for (row, cells) in grid.rows().enumerate() {
+        let mut col = 0;
+        while col < cells.len() {
+            let digits = cells[col..].iter().take_while(|ch| ch.is_ascii_digit()).count();
+            if digits == 0 {
+                col += 1;
+                continue;
+            }
+
+            let value = cells[col..col + digits]
+                .iter()
+                .filter_map(|ch| ch.to_digit(10))
+                .fold(0, |acc, digit| acc * 10 + digit as usize);
+            numbers.push(Number { value, row, cols: col..col + digits });
+            col += digits;
+        }
+    }
+    numbers
 }
 
-static NON_SYMBOLS_CHARS: [char; 13] = ['.', '\r', '\n', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
 fn is_symbol(ch: &char) -> bool {
-    !NON_SYMBOLS_CHARS.contains(ch)
+    *ch != '.' && !ch.is_ascii_digit()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parse_schematic;
+    use crate::{grid::{Grid, ParseGridError}, parse_schematic};
 
-    #[test]
-    pub fn parse_schematic_test() {
-        let input = "
+    pub static DEFAULT_INPUT: &str = "
 467..114..
 ...*......
 ..35..633.
@@ -118,16 +152,39 @@ mod tests {
 ...$.*....
 .664.598..";
 
-        let result = parse_schematic(input.trim());
-        
+    #[test]
+    pub fn parse_schematic_test() {
+        let grid: Grid<char> = DEFAULT_INPUT.trim().parse().unwrap();
+
+        let result: Vec<_> = parse_schematic(&grid)
+            .symbol_values()
+            .map(|(symbol, parts)| (symbol.position, parts))
+            .collect();
+
         let expected = vec![
-            (14, vec![467, 35]),
-            (39, vec![633]),
-            (47, vec![617]),
-            (60, vec![592]),
-            (91, vec![664]),
-            (93, vec![755, 598]),
+            ((1, 3), vec![467, 35]),
+            ((3, 6), vec![633]),
+            ((4, 3), vec![617]),
+            ((5, 5), vec![592]),
+            ((8, 3), vec![664]),
+            ((8, 5), vec![755, 598]),
         ];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn parse_schematic_line_endings_test() {
+        let input = "467..114\r\n...€....\r\n..35..6\r\n......#\r\n";
+
+        let strict = input.parse::<Grid<char>>();
+        let grid = Grid::parse_padded(input, '.');
+        let result: Vec<_> = parse_schematic(&grid)
+            .symbol_values()
+            .map(|(symbol, parts)| (symbol.position, symbol.ch, parts))
+            .collect();
+
+        assert_eq!(strict, Err(ParseGridError::Ragged { row: 2, width: 7, expected: 8 }));
+        let expected = vec![((1, 3), '€', vec![467, 35]), ((3, 6), '#', vec![6])];
+        assert_eq!(result, expected);
+    }
 }
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use crate::{gear::GearRule, grid::Grid, schematic::Schematic};
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Style {
+    Ansi,
+    Html,
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "ansi" => Ok(Style::Ansi),
+            "html" => Ok(Style::Html),
+            _ => Err(format!("unknown render style `{input}`, expected `ansi` or `html`")),
+        }
+    }
+}
+
+/// What the solver decided a cell is.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Kind {
+    Blank,
+    Part,
+    Loose,
+    Symbol,
+    Gear,
+}
+
+impl Kind {
+    fn ansi(&self) -> Option<&'static str> {
+        match self {
+            Kind::Blank => None,
+            Kind::Part => Some("\x1b[32m"),
+            Kind::Loose => Some("\x1b[31m"),
+            Kind::Symbol => Some("\x1b[33m"),
+            Kind::Gear => Some("\x1b[1;35m"),
+        }
+    }
+
+    fn class(&self) -> Option<&'static str> {
+        match self {
+            Kind::Blank => None,
+            Kind::Part => Some("part"),
+            Kind::Loose => Some("loose"),
+            Kind::Symbol => Some("symbol"),
+            Kind::Gear => Some("gear"),
+        }
+    }
+}
+
+static HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Engine schematic</title>
+<style>
+body { background: #111; color: #666; }
+.part { color: #4c4; }
+.loose { color: #e44; }
+.symbol { color: #dc3; }
+.gear { color: #d4d; font-weight: bold; }
+</style>
+</head>
+<body>
+<pre>
+"#;
+static HTML_FOOTER: &str = "</pre>\n</body>\n</html>\n";
+
+/// Draws `grid` with part numbers, loose numbers, symbols and the gears picked by `gear_rule`
+/// each highlighted, exactly as recorded in `schematic`.
+pub fn render(grid: &Grid<char>, schematic: &Schematic, gear_rule: &GearRule, style: Style) -> String {
+    let kinds = classify(grid, schematic, gear_rule);
+
+    let mut output = String::new();
+    if style == Style::Html {
+        output.push_str(HTML_HEADER);
+    }
+
+    for (cells, kinds) in grid.rows().zip(kinds.rows()) {
+        let mut current = Kind::Blank;
+        for (&ch, &kind) in cells.iter().zip(kinds) {
+            if kind != current {
+                close(&mut output, current, style);
+                open(&mut output, kind, style);
+                current = kind;
+            }
+            match (style, ch) {
+                (Style::Html, '&') => output.push_str("&amp;"),
+                (Style::Html, '<') => output.push_str("&lt;"),
+                (Style::Html, '>') => output.push_str("&gt;"),
+                _ => output.push(ch),
+            }
+        }
+        close(&mut output, current, style);
+        output.push('\n');
+    }
+
+    if style == Style::Html {
+        output.push_str(HTML_FOOTER);
+    }
+    output
+}
+
+fn open(output: &mut String, kind: Kind, style: Style) {
+    match style {
+        Style::Ansi => output.push_str(kind.ansi().unwrap_or_default()),
+        Style::Html => {
+            if let Some(class) = kind.class() {
+                output.push_str(&format!("<span class=\"{class}\">"));
+            }
+        }
+    }
+}
+
+fn close(output: &mut String, kind: Kind, style: Style) {
+    if kind == Kind::Blank {
+        return;
+    }
+    match style {
+        Style::Ansi => output.push_str("\x1b[0m"),
+        Style::Html => output.push_str("</span>"),
+    }
+}
+
+fn classify(grid: &Grid<char>, schematic: &Schematic, gear_rule: &GearRule) -> Grid<Kind> {
+    let mut kinds: Vec<Vec<Kind>> = grid.rows().map(|cells| vec![Kind::Blank; cells.len()]).collect();
+
+    for (i, number) in schematic.numbers.iter().enumerate() {
+        let kind = if schematic.symbols_touching(i).is_empty() {
+            Kind::Loose
+        } else {
+            Kind::Part
+        };
+        kinds[number.row][number.cols.clone()].fill(kind);
+    }
+    for symbol in &schematic.symbols {
+        let (row, col) = symbol.position;
+        kinds[row][col] = Kind::Symbol;
+    }
+    for (symbol, _) in gear_rule.gears(schematic) {
+        let (row, col) = symbol.position;
+        kinds[row][col] = Kind::Gear;
+    }
+
+    Grid::from_rows(kinds).expect("rows of a grid are all the same width")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schematic;
+
+    static INPUT: &str = "
+467..114..
+...*.....<
+..35......";
+
+    #[test]
+    pub fn render_ansi_test() {
+        let grid: Grid<char> = INPUT.trim().parse().unwrap();
+        let schematic = parse_schematic(&grid);
+
+        let result = render(&grid, &schematic, &GearRule::default(), Style::Ansi);
+
+        let expected = "\x1b[32m467\x1b[0m..\x1b[31m114\x1b[0m..
+...\x1b[1;35m*\x1b[0m.....\x1b[33m<\x1b[0m
+..\x1b[32m35\x1b[0m......
+";
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn render_html_test() {
+        let grid: Grid<char> = INPUT.trim().parse().unwrap();
+        let schematic = parse_schematic(&grid);
+
+        let result = render(&grid, &schematic, &GearRule::default(), Style::Html);
+
+        let expected = r#"<span class="part">467</span>..<span class="loose">114</span>..
+...<span class="gear">*</span>.....<span class="symbol">&lt;</span>
+..<span class="part">35</span>......
+"#;
+        assert!(result.starts_with("<!DOCTYPE html>"));
+        assert!(result.contains(expected));
+        assert!(result.ends_with(HTML_FOOTER));
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+use crate::grid::Position;
+
+/// A number in the schematic, whether or not it touches a symbol.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Number {
+    pub value: usize,
+    pub row: usize,
+    pub cols: Range<usize>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Symbol {
+    pub position: Position,
+    pub ch: char,
+}
+
+/// Every number and symbol in a schematic, and which of them touch.
+///
+/// Numbers and symbols are in reading order and referred to by their index.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    symbol_numbers: Vec<Vec<usize>>,
+    number_symbols: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    pub fn new(numbers: Vec<Number>, symbols: Vec<Symbol>) -> Self {
+        Self {
+            symbol_numbers: vec![vec![]; symbols.len()],
+            number_symbols: vec![vec![]; numbers.len()],
+            numbers,
+            symbols,
+        }
+    }
+
+    /// Records that the symbol and number at these indices touch.
+    pub fn connect(&mut self, symbol: usize, number: usize) {
+        if !self.symbol_numbers[symbol].contains(&number) {
+            self.symbol_numbers[symbol].push(number);
+            self.number_symbols[number].push(symbol);
+        }
+    }
+
+    /// Indices of the numbers touching `symbol`, in the order they were found.
+    pub fn numbers_touching(&self, symbol: usize) -> &[usize] {
+        &self.symbol_numbers[symbol]
+    }
+
+    /// Indices of the symbols touching `number`.
+    pub fn symbols_touching(&self, number: usize) -> &[usize] {
+        &self.number_symbols[number]
+    }
+
+    /// Numbers touching at least one symbol, each counted once.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    /// Numbers touching no symbol.
+    pub fn loose_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    /// Each symbol with the values of the numbers touching it.
+    pub fn symbol_values(&self) -> impl Iterator<Item = (&Symbol, Vec<usize>)> {
+        self.symbols.iter().zip(&self.symbol_numbers).map(|(symbol, numbers)| {
+            (symbol, numbers.iter().map(|&i| self.numbers[i].value).collect())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{grid::Grid, parse_schematic, tests::DEFAULT_INPUT};
+
+    #[test]
+    pub fn schematic_adjacency_test() {
+        let grid: Grid<char> = DEFAULT_INPUT.trim().parse().unwrap();
+
+        let schematic = parse_schematic(&grid);
+
+        let loose: Vec<_> = schematic.loose_numbers().map(|number| number.value).collect();
+        let parts: usize = schematic.part_numbers().map(|number| number.value).sum();
+        let touching_35: Vec<_> = schematic
+            .symbols_touching(2)
+            .iter()
+            .map(|&i| &schematic.symbols[i])
+            .map(|symbol| (symbol.position, symbol.ch))
+            .collect();
+
+        assert_eq!(loose, vec![114, 58]);
+        assert_eq!(parts, 4361);
+        assert_eq!(schematic.numbers[2].value, 35);
+        assert_eq!(touching_35, vec![((1, 3), '*')]);
+    }
+
+    #[test]
+    pub fn schematic_shared_number_test() {
+        let grid: Grid<char> = "
+.....
+.*12#
+.....".trim().parse().unwrap();
+
+        let schematic = parse_schematic(&grid);
+
+        let parts: Vec<_> = schematic.part_numbers().map(|number| number.value).collect();
+        assert_eq!(parts, vec![12]);
+        assert_eq!(schematic.symbols_touching(0), &[0, 1]);
+        assert_eq!(schematic.numbers_touching(1), &[0]);
+    }
+}
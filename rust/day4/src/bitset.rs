@@ -0,0 +1,106 @@
+use std::ops::BitAnd;
+
+/// A set of small numbers, one bit per number in `0..WORDS * 64`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct BitSet<const WORDS: usize> {
+    words: [u64; WORDS],
+}
+
+/// The set scratchcards use: numbers `0..128`.
+pub type NumberSet = BitSet<2>;
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> Self {
+        Self { words: [0; WORDS] }
+    }
+}
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    pub const CAPACITY: usize = WORDS * 64;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `num`, returning `false` if it is too big to fit.
+    pub fn insert(&mut self, num: usize) -> bool {
+        if num >= Self::CAPACITY {
+            return false;
+        }
+        self.words[num / 64] |= 1 << (num % 64);
+        true
+    }
+
+    pub fn contains(&self, num: usize) -> bool {
+        num < Self::CAPACITY && self.words[num / 64] & (1 << (num % 64)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// The numbers in the set, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+impl<const WORDS: usize> BitAnd for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        let mut words = self.words;
+        for (word, other) in words.iter_mut().zip(other.words) {
+            *word &= other;
+        }
+        Self { words }
+    }
+}
+
+/// Collects numbers into a set.
+///
+/// # Panics
+///
+/// If a number does not fit in the set.
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for num in iter {
+            assert!(set.insert(num), "{num} does not fit in a set of {} numbers", Self::CAPACITY);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn bitset_test() {
+        let a: NumberSet = [0, 5, 63, 64, 127].into_iter().collect();
+        let b: NumberSet = [5, 64, 100, 127].into_iter().collect();
+
+        let both = a & b;
+
+        assert_eq!(both.iter().collect::<Vec<_>>(), vec![5, 64, 127]);
+        assert_eq!(both.len(), 3);
+        assert!(a.contains(63) && !a.contains(62) && !a.contains(128));
+        assert!(!NumberSet::new().insert(128));
+        assert!(NumberSet::new().is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+use std::{fmt, str::FromStr};
+
+use crate::bitset::NumberSet;
+
+/// A scratchcard. Every number on it is below `NumberSet::CAPACITY`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Card {
+    pub id: usize,
+    pub winning: NumberSet,
+    /// The numbers on the right of the `|`, in the order they were written.
+    chosen: Vec<usize>,
+    chosen_set: NumberSet,
+}
+
+impl Card {
+    pub fn new(id: usize, winning: NumberSet, chosen: Vec<usize>) -> Self {
+        let chosen_set = chosen.iter().copied().collect();
+        Self {
+            id,
+            winning,
+            chosen,
+            chosen_set,
+        }
+    }
+
+    /// The numbers on the right of the `|`, in the order they were written.
+    pub fn chosen(&self) -> &[usize] {
+        &self.chosen
+    }
+
+    /// The chosen numbers that are also winning numbers, sorted. A number chosen twice counts twice.
+    pub fn matches(&self) -> Vec<usize> {
+        let mut matches: Vec<usize> = self.chosen().iter().copied().filter(|&num| self.winning.contains(num)).collect();
+        matches.sort();
+        matches
+    }
+
+    /// How many chosen numbers are winning numbers, counting repeats like `matches` does.
+    pub fn match_count(&self) -> usize {
+        if self.chosen_set.len() == self.chosen.len() {
+            return (self.winning & self.chosen_set).len();
+        }
+        // Some number was chosen more than once, which the set cannot count.
+        self.chosen.iter().filter(|&&num| self.winning.contains(num)).count()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseCardError {
+    MissingColon,
+    MissingBar,
+    InvalidId(String),
+    InvalidNumber(String),
+    NumberTooBig(usize),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColon => write!(f, "expected `Card <id>: <numbers>`"),
+            Self::MissingBar => write!(f, "expected `<winning numbers> | <chosen numbers>`"),
+            Self::InvalidId(id) => write!(f, "invalid card id `{id}`"),
+            Self::InvalidNumber(num) => write!(f, "invalid number `{num}`"),
+            Self::NumberTooBig(num) => write!(f, "{num} is not below {}", NumberSet::CAPACITY),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (id, results) = input.split_once(':').ok_or(ParseCardError::MissingColon)?;
+        let (winning, chosen) = results.split_once('|').ok_or(ParseCardError::MissingBar)?;
+
+        let id = id
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| ParseCardError::InvalidId(id.to_string()))?;
+
+        Ok(Card::new(id, parse_numbers(winning)?, parse_numbers(chosen)?))
+    }
+}
+
+fn parse_numbers<C: FromIterator<usize>>(input: &str) -> Result<C, ParseCardError> {
+    input
+        .split_whitespace()
+        .map(|num| match num.parse() {
+            Ok(num) if num < NumberSet::CAPACITY => Ok(num),
+            Ok(num) => Err(ParseCardError::NumberTooBig(num)),
+            Err(_) => Err(ParseCardError::InvalidNumber(num.to_string())),
+        })
+        .collect()
+}
+
+/// A problem with the sequence of card ids, which should count up from 1 without gaps.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum IdIssue {
+    /// Ids between the previous card and the card at `index` are missing.
+    Gap { index: usize, missing: std::ops::Range<usize> },
+    /// The card at `index` has an id no greater than the card before it.
+    OutOfOrder { index: usize, id: usize, previous: usize },
+}
+
+impl fmt::Display for IdIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gap { index, missing } if missing.len() == 1 => {
+                write!(f, "card {} is missing before line {}", missing.start, index + 1)
+            }
+            Self::Gap { index, missing } => write!(
+                f,
+                "cards {}-{} are missing before line {}",
+                missing.start,
+                missing.end - 1,
+                index + 1
+            ),
+            Self::OutOfOrder { index, id, previous } => {
+                write!(f, "card {id} on line {} comes after card {previous}", index + 1)
+            }
+        }
+    }
+}
+
+pub fn check_ids(cards: &[Card]) -> Vec<IdIssue> {
+    let mut issues = vec![];
+    let mut previous = 0;
+    for (index, card) in cards.iter().enumerate() {
+        if card.id <= previous {
+            issues.push(IdIssue::OutOfOrder {
+                index,
+                id: card.id,
+                previous,
+            });
+            continue;
+        }
+        if card.id > previous + 1 {
+            issues.push(IdIssue::Gap {
+                index,
+                missing: previous + 1..card.id,
+            });
+        }
+        previous = card.id;
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn card_from_str_test() {
+        let result: Card = "Card  12: 41 48 83 | 83 86  6 48 83".parse().unwrap();
+
+        let expected = Card::new(12, [41, 48, 83].into_iter().collect(), vec![83, 86, 6, 48, 83]);
+        assert_eq!(result, expected);
+        assert_eq!(result.chosen(), [83, 86, 6, 48, 83]);
+        assert_eq!(result.matches(), vec![48, 83, 83]);
+        assert_eq!(result.match_count(), 3);
+    }
+
+    #[test]
+    pub fn card_from_str_error_test() {
+        let inputs = ["Card 1 41 | 83", "Card 1: 41 83", "Crd 1: 41 | 83", "Card 1: 41 | x3", "Card 1: 128 | 3"];
+
+        let result: Vec<_> = inputs
+            .iter()
+            .map(|input| input.parse::<Card>().unwrap_err())
+            .collect();
+
+        let expected = vec![
+            ParseCardError::MissingColon,
+            ParseCardError::MissingBar,
+            ParseCardError::InvalidId("Crd 1".to_string()),
+            ParseCardError::InvalidNumber("x3".to_string()),
+            ParseCardError::NumberTooBig(128),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn check_ids_test() {
+        let cards: Vec<Card> = [1, 2, 5, 4, 6, 8]
+            .iter()
+            .map(|id| format!("Card {id}: 1 | 2").parse().unwrap())
+            .collect();
+
+        let result = check_ids(&cards);
+
+        let expected = vec![
+            IdIssue::Gap { index: 2, missing: 3..5 },
+            IdIssue::OutOfOrder { index: 3, id: 4, previous: 5 },
+            IdIssue::Gap { index: 5, missing: 7..8 },
+        ];
+        assert_eq!(result, expected);
+        assert_eq!(result[0].to_string(), "cards 3-4 are missing before line 3");
+        assert_eq!(result[2].to_string(), "card 7 is missing before line 6");
+    }
+}
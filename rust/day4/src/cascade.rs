@@ -0,0 +1,176 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::{card::Card, scoring::Scoring};
+
+/// The number of copies of some card no longer fits in a `u128`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct CascadeOverflow {
+    /// Index of the card whose copies overflowed.
+    pub card: usize,
+}
+
+impl fmt::Display for CascadeOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "scratchcard count overflowed at card {}", self.card + 1)
+    }
+}
+
+impl std::error::Error for CascadeOverflow {}
+
+/// Counts every scratchcard, originals and copies, given each card's match count in order.
+///
+/// Only the copies already won for the next few cards are kept, in a ring buffer that grows to
+/// the largest match count seen, so memory does not depend on how many cards there are. Copies
+/// won for cards past the end of the input are dropped.
+pub fn count_scratchcards(match_counts: impl IntoIterator<Item = usize>) -> Result<u128, CascadeOverflow> {
+    let mut pending: VecDeque<u128> = VecDeque::new();
+    let mut total: u128 = 0;
+
+    for (card, matches) in match_counts.into_iter().enumerate() {
+        let overflow = CascadeOverflow { card };
+        let copies = pending.pop_front().unwrap_or(0).checked_add(1).ok_or(overflow)?;
+        total = total.checked_add(copies).ok_or(overflow)?;
+
+        if pending.len() < matches {
+            pending.resize(matches, 0);
+        }
+        for won in pending.iter_mut().take(matches) {
+            *won = won.checked_add(copies).ok_or(overflow)?;
+        }
+    }
+    Ok(total)
+}
+
+/// What one card contributed to the cascade.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TraceEntry {
+    pub card: usize,
+    pub matches: usize,
+    pub points: usize,
+    /// Instances of this card, the original included.
+    pub copies: u128,
+    /// Ids of the later cards each instance wins a copy of.
+    pub won: Vec<usize>,
+}
+
+/// Plays out the cascade card by card, recording how many copies of each card there were.
+pub fn trace_cascade(cards: &[Card], scoring: &Scoring) -> Result<Vec<TraceEntry>, CascadeOverflow> {
+    let mut copies = vec![1u128; cards.len()];
+    let mut trace = Vec::with_capacity(cards.len());
+
+    for (i, card) in cards.iter().enumerate() {
+        let matches = card.match_count();
+        let won = &cards[i + 1..(i + 1 + matches).min(cards.len())];
+        for j in i + 1..i + 1 + won.len() {
+            copies[j] = copies[j].checked_add(copies[i]).ok_or(CascadeOverflow { card: j })?;
+        }
+
+        trace.push(TraceEntry {
+            card: card.id,
+            matches,
+            points: scoring.score(matches),
+            copies: copies[i],
+            won: won.iter().map(|card| card.id).collect(),
+        });
+    }
+    Ok(trace)
+}
+
+/// One row per card, with the ids of won cards separated by spaces.
+pub fn trace_to_csv(trace: &[TraceEntry]) -> String {
+    let mut csv = String::from("card,matches,points,copies,won\n");
+    for entry in trace {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            entry.card,
+            entry.matches,
+            entry.points,
+            entry.copies,
+            join(&entry.won, " ")
+        ));
+    }
+    csv
+}
+
+/// An array with one object per card. `copies` is written as a string since it may not fit in a
+/// JSON number.
+pub fn trace_to_json(trace: &[TraceEntry]) -> String {
+    let entries: Vec<String> = trace
+        .iter()
+        .map(|entry| {
+            format!(
+                "{{\"card\":{},\"matches\":{},\"points\":{},\"copies\":\"{}\",\"won\":[{}]}}",
+                entry.card,
+                entry.matches,
+                entry.points,
+                entry.copies,
+                join(&entry.won, ",")
+            )
+        })
+        .collect();
+    format!("[{}]\n", entries.join(",\n"))
+}
+
+fn join(ids: &[usize], separator: &str) -> String {
+    ids.iter().map(usize::to_string).collect::<Vec<_>>().join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_cards, tests::DEFAULT_INPUT};
+
+    #[test]
+    pub fn trace_cascade_test() {
+        let cards = parse_cards(DEFAULT_INPUT.trim()).unwrap();
+
+        let result = trace_cascade(&cards, &Scoring::Linear).unwrap();
+
+        let copies: Vec<_> = result.iter().map(|entry| entry.copies).collect();
+        assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(copies.iter().sum::<u128>(), count_scratchcards(cards.iter().map(Card::match_count)).unwrap());
+        assert_eq!(result[1], TraceEntry { card: 2, matches: 2, points: 2, copies: 2, won: vec![3, 4] });
+    }
+
+    #[test]
+    pub fn trace_export_test() {
+        let cards = parse_cards("Card 1: 1 2 | 1 2\nCard 2: 1 | 1\nCard 3: 1 | 2").unwrap();
+        let trace = trace_cascade(&cards, &Scoring::Doubling).unwrap();
+
+        let csv = trace_to_csv(&trace);
+        let json = trace_to_json(&trace);
+
+        assert_eq!(csv, "card,matches,points,copies,won
+1,2,2,1,2 3
+2,1,1,2,3
+3,0,0,4,
+");
+        assert_eq!(json, r#"[{"card":1,"matches":2,"points":2,"copies":"1","won":[2,3]},
+{"card":2,"matches":1,"points":1,"copies":"2","won":[3]},
+{"card":3,"matches":0,"points":0,"copies":"4","won":[]}]
+"#);
+    }
+
+    #[test]
+    pub fn count_scratchcards_test() {
+        let result = count_scratchcards([4, 2, 2, 1, 0, 0]);
+
+        assert_eq!(result, Ok(30));
+    }
+
+    #[test]
+    pub fn count_scratchcards_past_end_test() {
+        let result = count_scratchcards([3, 1]);
+
+        assert_eq!(result, Ok(3));
+    }
+
+    #[test]
+    pub fn count_scratchcards_overflow_test() {
+        let exponential = std::iter::repeat_n(2, 200);
+
+        let result = count_scratchcards(exponential);
+
+        assert!(matches!(result, Err(CascadeOverflow { card }) if card > 100 && card < 200));
+    }
+}
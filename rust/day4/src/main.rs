@@ -1,92 +1,116 @@
-use std::fs;
+use std::{env, fs, process};
+
+use card::{Card, ParseCardError};
+use cascade::CascadeOverflow;
+use scoring::Scoring;
+
+mod bitset;
+mod card;
+mod cascade;
+mod scoring;
 
 fn main() {
+    let Args { scoring, report, trace } = parse_args(env::args().skip(1)).unwrap();
     let input = get_input();
 
-    let winning_numbers = parse_winning_numbers(&input);
-    let part_1 = get_total_points(winning_numbers.iter());
+    if report {
+        self::report(&parse_cards(&input).unwrap());
+        return;
+    }
+
+    if let Some(format) = trace {
+        let trace = cascade::trace_cascade(&parse_cards(&input).unwrap(), &scoring).unwrap();
+        match format {
+            TraceFormat::Csv => print!("{}", cascade::trace_to_csv(&trace)),
+            TraceFormat::Json => print!("{}", cascade::trace_to_json(&trace)),
+        }
+        return;
+    }
+
+    let cards = parse_cards(&input).unwrap();
+    let part_1 = get_total_points(cards.iter().map(Card::match_count), &scoring);
     println!("Part 1 result: {}", part_1);
 
-    let part_2 = get_total_scratchcards(winning_numbers.iter());
-    println!("Part 2 result: {}", part_2);
+    match get_total_scratchcards(&input) {
+        Ok(part_2) => println!("Part 2 result: {}", part_2),
+        Err(error) => {
+            eprintln!("{error}");
+            process::exit(1);
+        }
+    }
 }
 
 pub fn get_input() -> String {
     String::from_utf8(fs::read("input").unwrap()).unwrap()
 }
 
-pub fn parse_winning_numbers(input: &str) -> Vec<Vec<usize>> {
-    input
-        .lines()
-        .map(|line| {
-            let (_, results) = line.split_once(':').unwrap();
-            let (winning_numbers, chosen_numbers) = results.split_once('|').unwrap();
-            
-            let mut winning_numbers: Vec<usize> = winning_numbers
-                .split(' ')
-                .filter_map(|s| s.parse::<usize>().ok())
-                .collect();
-            winning_numbers.sort();
-
-            let mut chosen_numbers: Vec<usize> = chosen_numbers
-                .split(' ')
-                .filter_map(|s| s.trim().parse::<usize>().ok())
-                .filter(|num| winning_numbers.contains(num))
-                .collect();
-            chosen_numbers.sort();
-    
-            chosen_numbers
-        })
-        .collect()
+enum TraceFormat {
+    Csv,
+    Json,
 }
 
-pub fn get_total_points<'a, Iter>(iter: Iter) -> usize 
-where Iter: Iterator<Item = &'a Vec<usize>>
-{
-    iter
-        .filter(|nums| nums.len() > 0)
-        .fold(0, |acc, nums| acc + (1 << (nums.len() - 1)))
+struct Args {
+    scoring: Scoring,
+    report: bool,
+    trace: Option<TraceFormat>,
 }
 
-pub fn get_total_scratchcards<'a, Iter>(iter: Iter) -> usize
-where Iter: Iterator<Item = &'a Vec<usize>>
-{
-    iter
-        .enumerate()
-        .fold(Vec::new(), |mut state, (i, nums)| {
-            let amount = match state.get(i) {
-                Some(x) => {
-                    state[i] = x + 1;
-                    state[i]
-                },
-                None => {
-                    state.insert(i, 1);
-                    1
-                }
-            };
-
-            let len = nums.len();
-            if len != 0 {
-                for i in i+1..i+len+1 {
-                    match state.get(i) {
-                        Some(x) => state[i] = x + amount,
-                        None => state.insert(i, amount),
-                    }
+/// `[--scoring doubling|linear|table:<points>,...] [--report | --trace csv|json]`
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut scoring = Scoring::default();
+    let mut report = false;
+    let mut trace = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scoring" => scoring = args.next().ok_or("--scoring needs a value")?.parse()?,
+            "--report" => report = true,
+            "--trace" => {
+                trace = match args.next().as_deref() {
+                    Some("csv") => Some(TraceFormat::Csv),
+                    Some("json") => Some(TraceFormat::Json),
+                    _ => return Err("--trace needs `csv` or `json`".to_string()),
                 }
             }
-            state
-        })
-        .iter()
-        .fold(0, |acc, amount| acc + amount)
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    Ok(Args { scoring, report, trace })
+}
+
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, ParseCardError> {
+    input.lines().map(str::parse).collect()
+}
+
+fn report(cards: &[Card]) {
+    for card in cards {
+        println!("Card {}: {} matches {:?}", card.id, card.match_count(), card.matches());
+    }
+    for issue in card::check_ids(cards) {
+        println!("{issue}");
+    }
+}
+
+pub fn get_total_points<Iter>(match_counts: Iter, scoring: &Scoring) -> usize
+where Iter: Iterator<Item = usize>
+{
+    match_counts
+        .map(|matches| scoring.score(matches))
+        .fold(0, usize::saturating_add)
+}
+
+/// Counts the scratchcards one line at a time, without holding every card.
+///
+/// Panics if a card does not parse; `main` has already parsed them all for part 1.
+pub fn get_total_scratchcards(input: &str) -> Result<u128, CascadeOverflow> {
+    let match_counts = input.lines().map(|line| line.parse::<Card>().map(|card| card.match_count()));
+    cascade::count_scratchcards(match_counts.map(|count| count.expect("every card should parse")))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    pub fn parse_winning_numbers_test() {
-        let input = "
+    pub static DEFAULT_INPUT: &str = "
 Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
@@ -94,7 +118,10 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-        let result = parse_winning_numbers(input.trim());
+    #[test]
+    pub fn card_matches_test() {
+        let cards = parse_cards(DEFAULT_INPUT.trim()).unwrap();
+        let result: Vec<Vec<usize>> = cards.iter().map(Card::matches).collect();
 
         let expected = vec![
             vec![17, 48, 83, 86],
@@ -109,16 +136,8 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     pub fn get_total_points_test() {
-        let input = "
-Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-
-        let numbers = parse_winning_numbers(input.trim());
-        let result = get_total_points(numbers.iter());
+        let cards = parse_cards(DEFAULT_INPUT.trim()).unwrap();
+        let result = get_total_points(cards.iter().map(Card::match_count), &Scoring::Doubling);
 
         let expected = 13;
         assert_eq!(result, expected);
@@ -126,19 +145,9 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     pub fn get_total_scratchcards_test() {
-        
-        let input = "
-Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-
-        let numbers = parse_winning_numbers(input.trim());
-        let result = get_total_scratchcards(numbers.iter());
+        let result = get_total_scratchcards(DEFAULT_INPUT.trim());
 
-        let expected = 30;
+        let expected = Ok(30);
         assert_eq!(result, expected);
     }
 }
@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+/// How many points a card is worth for its number of matches.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub enum Scoring {
+    /// 1 point for the first match, doubled for every match after it, up to `usize::MAX`.
+    #[default]
+    Doubling,
+    /// 1 point per match.
+    Linear,
+    /// `table[matches]` points, or the last entry for more matches than the table covers.
+    Table(Vec<usize>),
+}
+
+impl Scoring {
+    pub fn score(&self, matches: usize) -> usize {
+        match self {
+            Scoring::Doubling if matches == 0 => 0,
+            Scoring::Doubling => u32::try_from(matches - 1)
+                .ok()
+                .and_then(|shift| 1usize.checked_shl(shift))
+                .unwrap_or(usize::MAX),
+            Scoring::Linear => matches,
+            Scoring::Table(table) => table
+                .get(matches)
+                .or(table.last())
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Parses `doubling`, `linear` or `table:<points for 0 matches>,<for 1>,...`.
+impl FromStr for Scoring {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "doubling" => Ok(Scoring::Doubling),
+            "linear" => Ok(Scoring::Linear),
+            _ => {
+                let table = input
+                    .strip_prefix("table:")
+                    .ok_or_else(|| format!("unknown scoring `{input}`, expected `doubling`, `linear` or `table:...`"))?;
+                table
+                    .split(',')
+                    .map(|points| points.trim().parse().map_err(|_| format!("invalid points `{points}`")))
+                    .collect::<Result<_, _>>()
+                    .map(Scoring::Table)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn scoring_test() {
+        let rules = ["doubling", "linear", "table:0,1,3,6"];
+
+        let result: Vec<Vec<usize>> = rules
+            .iter()
+            .map(|rule| {
+                let scoring: Scoring = rule.parse().unwrap();
+                (0..6).map(|matches| scoring.score(matches)).collect()
+            })
+            .collect();
+
+        let expected = vec![
+            vec![0, 1, 2, 4, 8, 16],
+            vec![0, 1, 2, 3, 4, 5],
+            vec![0, 1, 3, 6, 6, 6],
+        ];
+        assert_eq!(result, expected);
+        assert_eq!(Scoring::Doubling.score(64), 1 << 63);
+        assert_eq!(Scoring::Doubling.score(65), usize::MAX);
+        assert!("table:1,x".parse::<Scoring>().is_err());
+        assert!("squared".parse::<Scoring>().is_err());
+    }
+}
@@ -1,21 +1,62 @@
-use std::{ops::Range, fs};
+use std::{env, fmt, ops::Range, fs};
+
+mod plot;
+mod ranges;
+mod report;
 
 fn main() {
+    let Args { check, plot } = parse_args(env::args().skip(1)).unwrap();
     let input = get_input();
 
+    if check {
+        let findings = report::check(&input);
+        for finding in &findings {
+            println!("{finding}");
+        }
+        println!("{} findings", findings.len());
+        return;
+    }
+
     let almanac = Almanac::from_str(&input);
 
     let part_1 = part_1(&almanac);
     println!("Part 1 result: {part_1}");
 
     let part_2 = part_2(&almanac);
-    println!("Part 2 result: {part_2}");
+    match part_2 {
+        Some(part_2) => println!("Part 2 result: {part_2}"),
+        None => println!("Part 2 result: no seeds are planted"),
+    }
+
+    if let Some(path) = plot {
+        fs::write(&path, plot::plot(&almanac, part_2)).unwrap();
+        println!("Plot written to {path}");
+    }
 }
 
 fn get_input() -> String {
     String::from_utf8(fs::read("input").unwrap()).unwrap()
 }
 
+struct Args {
+    check: bool,
+    plot: Option<String>,
+}
+
+/// `[--check] [--plot <svg path>]`
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut check = false;
+    let mut plot = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--plot" => plot = Some(args.next().ok_or("--plot needs a path")?),
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    Ok(Args { check, plot })
+}
+
 fn part_1(almanac: &Almanac) -> usize {
     almanac.seeds
         .iter()
@@ -24,29 +65,24 @@ fn part_1(almanac: &Almanac) -> usize {
         .unwrap()
 }
 
-fn part_2(almanac: &Almanac) -> usize {
-    let mut seeds = almanac.seeds.iter();
-    let mut total_seeds = vec![];
-    while let Some(&seed) = seeds.next() {
-        let range = seeds.next().unwrap();
-        total_seeds.push(seed..seed+range);
-    }
-
-    (0..).find_map(|x| {
-        let humidity = almanac.humidity_to_location.get_from(&x);
-        let temperature = almanac.temperature_to_humidity.get_from(&humidity);
-        let light = almanac.light_to_temperature.get_from(&temperature);
-        let water = almanac.water_to_light.get_from(&light);
-        let fertilizer = almanac.fertilizer_to_water.get_from(&water);
-        let soil = almanac.soil_to_fertilizer.get_from(&fertilizer);
-        let seed = almanac.seed_to_soil.get_from(&soil);
-
-        if total_seeds.iter().any(|s| s.contains(&seed)) {
-            Some(x)
+/// The lowest location of any planted seed, or `None` if no seeds are planted.
+fn part_2(almanac: &Almanac) -> Option<usize> {
+    let bound = almanac.upper_bound();
+    if almanac.planted_seeds_for_locations(0..bound).is_empty() {
+        return None;
+    }
+
+    // Whether any planted seed lands in `0..=location` only flips once, from false to true.
+    let (mut low, mut high) = (0, bound);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if almanac.planted_seeds_for_locations(0..mid + 1).is_empty() {
+            low = mid + 1;
         } else {
-            None
+            high = mid;
         }
-    }).unwrap()
+    }
+    Some(low)
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -68,7 +104,7 @@ impl Almanac {
         let seeds = parse_seed_line(lines.next().unwrap());
 
         let maps = lines.fold(Vec::new(), |mut state, line| {
-            if line.len() == 0 {
+            if line.is_empty() {
                 return state;
             }
 
@@ -82,10 +118,7 @@ impl Almanac {
             let source = nums.next().unwrap();
             let range = nums.next().unwrap();
 
-            let i = state.len() - 1;
-            let last_map = state.get_mut(i).unwrap();
-            last_map.push_from(source..source+range);
-            last_map.push_to(dest..dest+range);
+            state.last_mut().unwrap().entry(dest, source, range);
             state
         });
 
@@ -110,8 +143,85 @@ impl Almanac {
         let humidity = self.temperature_to_humidity.get_to(&temperature);
         self.humidity_to_location.get_to(&humidity)
     }
+
+    /// The seed line read as `start length` pairs, as in part 2. An unpaired last value is left
+    /// out; `report::check` points it out.
+    pub fn seed_ranges(&self) -> Vec<Range<usize>> {
+        self.seeds
+            .chunks_exact(2)
+            .map(|pair| pair[0]..pair[0] + pair[1])
+            .collect()
+    }
+
+    /// The maps from seed to location, in the order they are applied.
+    pub fn maps(&self) -> [&Map; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
+    }
+
+    /// A value no seed, location or anything in between can reach.
+    fn upper_bound(&self) -> usize {
+        let seeds = self.seed_ranges().into_iter().map(|range| range.end);
+        let maps = self
+            .maps()
+            .into_iter()
+            .flat_map(|map| map.from.iter().chain(&map.to).map(|range| range.end));
+        seeds.chain(maps).max().unwrap_or(0)
+    }
+
+    /// Every seed whose location falls in `locations`, as sorted, disjoint ranges.
+    pub fn seeds_for_locations(&self, locations: Range<usize>) -> Vec<Range<usize>> {
+        self.maps()
+            .iter()
+            .rev()
+            .fold(vec![locations], |ranges, map| {
+                ranges::merge(ranges.into_iter().flat_map(|range| map.get_from_range(range)).collect())
+            })
+    }
+
+    /// Like `seeds_for_locations`, but only the seeds planted according to `seed_ranges`.
+    pub fn planted_seeds_for_locations(&self, locations: Range<usize>) -> Vec<Range<usize>> {
+        let planted = ranges::merge(self.seed_ranges());
+        self.seeds_for_locations(locations)
+            .iter()
+            .flat_map(|seeds| planted.iter().filter_map(|planted| ranges::intersect(seeds, planted)))
+            .collect()
+    }
 }
 
+/// Writes the almanac in the puzzle's own format, which `Almanac::from_str` reads back.
+impl fmt::Display for Almanac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for seed in &self.seeds {
+            write!(f, " {seed}")?;
+        }
+        writeln!(f)?;
+
+        for (name, map) in MAP_NAMES.iter().zip(self.maps()) {
+            write!(f, "\n{name} map:\n{map}")?;
+        }
+        Ok(())
+    }
+}
+
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
 fn parse_seed_line(line: &str) -> Vec<usize> {
     let (_, seeds) = line.split_once(':').unwrap();
     seeds.trim().split(' ')
@@ -119,7 +229,7 @@ fn parse_seed_line(line: &str) -> Vec<usize> {
         .collect()
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
 pub struct Map {
     from: Vec<Range<usize>>,
     to: Vec<Range<usize>>,
@@ -142,7 +252,7 @@ impl Map {
                 let i = from_id - from_range.start;
                 to_range.start + i
             },
-            None => from_id.clone(),
+            None => *from_id,
         }
     }
 
@@ -155,29 +265,46 @@ impl Map {
                 let i = to_id - to_range.start;
                 from_range.start + i
             },
-            None => to_id.clone(),
+            None => *to_id,
         }
     }
 
-    pub fn push_from(&mut self, from: Range<usize>) {
-        self.from.push(from)
+    /// Every value that maps into `to`, as sorted, disjoint ranges.
+    ///
+    /// Like `get_to`, a value covered by more than one source range is mapped by the first.
+    pub fn get_from_range(&self, to: Range<usize>) -> Vec<Range<usize>> {
+        let mut from = vec![];
+        for (i, (from_range, to_range)) in self.from.iter().zip(&self.to).enumerate() {
+            let Some(hit) = ranges::intersect(&to, to_range) else {
+                continue;
+            };
+            let start = from_range.start + (hit.start - to_range.start);
+            let end = from_range.start + (hit.end - to_range.start);
+            from.extend(ranges::subtract(start..end, &self.from[..i]));
+        }
+        from.extend(ranges::subtract(to, &self.from));
+        ranges::merge(from)
     }
 
-    pub fn push_to(&mut self, to: Range<usize>) {
-        self.to.push(to)
+    /// Adds an entry mapping `source..source + length` onto `destination..destination + length`.
+    pub fn entry(&mut self, destination: usize, source: usize, length: usize) -> &mut Self {
+        self.from.push(source..source + length);
+        self.to.push(destination..destination + length);
+        self
     }
 
-    fn get_range_id(&self, ranges: &Vec<Range<usize>>, id: &usize) -> Option<usize> {
-        ranges
-            .iter()
-            .enumerate()
-            .find_map(|(i, range)| {
-                if range.contains(id) {
-                    Some(i)
-                } else {
-                    None
-                }
-            })
+    fn get_range_id(&self, ranges: &[Range<usize>], id: &usize) -> Option<usize> {
+        ranges.iter().position(|range| range.contains(id))
+    }
+}
+
+/// One `<destination> <source> <length>` line per entry.
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (from, to) in self.from.iter().zip(&self.to) {
+            writeln!(f, "{} {} {}", to.start, from.start, from.len())?;
+        }
+        Ok(())
     }
 }
 
@@ -185,7 +312,7 @@ impl Map {
 mod tests {
     use crate::*;
 
-    static DEFAULT_INPUT: &str = "
+    pub static DEFAULT_INPUT: &str = "
 seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -228,4 +355,105 @@ humidity-to-location map:
         let expected = vec![82, 43, 86, 35];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn get_from_range_test() {
+        let mut map = Map::new();
+        map.entry(100, 10, 10).entry(0, 15, 15);
+
+        let result = map.get_from_range(0..105);
+
+        // 100..105 come from 10..15 and 0..15 from 15..30, except 15..20 which the first range
+        // claims. Everything else outside both source ranges maps to itself.
+        assert_eq!(result, vec![0..15, 20..105]);
+    }
+
+    #[test]
+    pub fn seeds_for_locations_test() {
+        let almanac = Almanac::from_str(DEFAULT_INPUT.trim());
+
+        let seeds = almanac.seeds_for_locations(46..47);
+        let planted = almanac.planted_seeds_for_locations(0..47);
+
+        assert!(seeds.iter().all(|range| range.clone().all(|seed| almanac.get_location(&seed) == 46)));
+        assert!(seeds.iter().any(|range| range.contains(&82)));
+        assert_eq!(planted, vec![82..83]);
+        assert_eq!(part_2(&almanac), Some(46));
+    }
+
+    #[test]
+    pub fn odd_seed_count_test() {
+        let odd = Almanac::from_str(&DEFAULT_INPUT.trim().replace("seeds: 79 14 55 13", "seeds: 79 14 55"));
+        let unpaired = Almanac::from_str(&DEFAULT_INPUT.trim().replace("seeds: 79 14 55 13", "seeds: 79"));
+
+        assert_eq!(odd.seed_ranges(), vec![79..93]);
+        assert_eq!(part_2(&unpaired), None);
+    }
+
+    #[test]
+    pub fn display_test() {
+        let almanac = Almanac::from_str(DEFAULT_INPUT.trim());
+
+        let result = almanac.to_string();
+
+        assert_eq!(result, format!("{}\n", DEFAULT_INPUT.trim()));
+    }
+
+    /// A xorshift generator, so the round trip can be checked on many almanacs without a
+    /// dependency.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        fn value(&mut self) -> usize {
+            // Mostly small values, with the odd huge one to exercise wide ranges.
+            match self.below(4) {
+                0 => self.below(usize::MAX / 2),
+                _ => self.below(100),
+            }
+        }
+    }
+
+    fn random_almanac(rng: &mut Rng) -> Almanac {
+        let seeds = (0..rng.below(8)).map(|_| rng.value()).collect();
+        let mut maps = (0..7).map(|_| {
+            let mut map = Map::new();
+            for _ in 0..rng.below(6) {
+                map.entry(rng.value(), rng.value(), rng.value());
+            }
+            map
+        });
+        let mut map = || maps.next().unwrap();
+        Almanac {
+            seeds,
+            seed_to_soil: map(),
+            soil_to_fertilizer: map(),
+            fertilizer_to_water: map(),
+            water_to_light: map(),
+            light_to_temperature: map(),
+            temperature_to_humidity: map(),
+            humidity_to_location: map(),
+        }
+    }
+
+    #[test]
+    pub fn round_trip_test() {
+        let mut rng = Rng(0x2023_1205);
+
+        for _ in 0..500 {
+            let almanac = random_almanac(&mut rng);
+            let text = almanac.to_string();
+
+            let result = Almanac::from_str(&text);
+
+            assert_eq!(result, almanac, "\n{text}");
+            assert_eq!(result.to_string(), text);
+        }
+    }
 }
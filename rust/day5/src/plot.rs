@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use crate::{ranges, Almanac, Map, MAP_NAMES};
+
+const WIDTH: f64 = 600.0;
+const HEIGHT: f64 = 300.0;
+const MARGIN: f64 = 40.0;
+
+/// A straight part of a piecewise mapping: `from` maps onto `to..to + from.len()`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Piece {
+    pub from: Range<usize>,
+    pub to: usize,
+}
+
+impl Piece {
+    fn end(&self) -> usize {
+        self.to + self.from.len()
+    }
+}
+
+/// The pieces of `map` over `domain`, sorted by `from`. Values no entry covers get identity pieces.
+pub fn pieces(map: &Map, domain: Range<usize>) -> Vec<Piece> {
+    let mut pieces = vec![];
+    for (i, (from, to)) in map.from.iter().zip(&map.to).enumerate() {
+        let Some(hit) = ranges::intersect(&domain, from) else {
+            continue;
+        };
+        for part in ranges::subtract(hit, &map.from[..i]) {
+            let to = to.start + (part.start - from.start);
+            pieces.push(Piece { from: part, to });
+        }
+    }
+    for part in ranges::subtract(domain, &map.from) {
+        let to = part.start;
+        pieces.push(Piece { from: part, to });
+    }
+    pieces.sort_by_key(|piece| piece.from.start);
+    pieces
+}
+
+/// The pieces of the whole seed to location mapping over `domain`, sorted by `from`.
+pub fn composed_pieces(almanac: &Almanac, domain: Range<usize>) -> Vec<Piece> {
+    let bound = almanac.upper_bound().max(domain.end);
+    let start = vec![Piece { to: domain.start, from: domain }];
+    almanac.maps().iter().fold(start, |pieces, map| {
+        let next = self::pieces(map, 0..bound);
+        pieces
+            .iter()
+            .flat_map(|piece| {
+                let image = piece.to..piece.end();
+                next.iter().filter_map(move |next| {
+                    let hit = ranges::intersect(&image, &next.from)?;
+                    let from = piece.from.start + (hit.start - piece.to);
+                    Some(Piece {
+                        from: from..from + hit.len(),
+                        to: next.to + (hit.start - next.from.start),
+                    })
+                })
+            })
+            .collect()
+    })
+}
+
+/// An SVG with one panel per map and a last one for seed to location. Part 2's seed ranges are
+/// shaded on the x-axis of the panels that take seeds, and `minimum`, the lowest location any of
+/// them reaches, is marked on the last if there is one.
+pub fn plot(almanac: &Almanac, minimum: Option<usize>) -> String {
+    let bound = almanac.upper_bound().max(1);
+    let seeds = ranges::merge(almanac.seed_ranges());
+    let mark = minimum.and_then(|minimum| {
+        let seeds = almanac.planted_seeds_for_locations(minimum..minimum + 1);
+        seeds.first().map(|range| (range.start, minimum))
+    });
+
+    let mut panels: Vec<(String, Vec<Piece>)> = MAP_NAMES
+        .iter()
+        .zip(almanac.maps())
+        .map(|(name, map)| (name.to_string(), pieces(map, 0..bound)))
+        .collect();
+    panels.push(("seed-to-location".to_string(), composed_pieces(almanac, 0..bound)));
+
+    let count = panels.len();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n",
+        WIDTH + 2.0 * MARGIN,
+        count as f64 * (HEIGHT + 2.0 * MARGIN)
+    );
+    for (i, (name, pieces)) in panels.iter().enumerate() {
+        let panel = Panel { top: i as f64 * (HEIGHT + 2.0 * MARGIN) + MARGIN, bound };
+        svg.push_str(&panel.frame(name));
+        if i == 0 || i == count - 1 {
+            for range in &seeds {
+                svg.push_str(&panel.shade(range));
+            }
+        }
+        for piece in pieces {
+            svg.push_str(&panel.line(piece));
+        }
+        if let (Some((seed, minimum)), true) = (mark, i == count - 1) {
+            svg.push_str(&panel.mark(seed, minimum));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Scales values in `0..bound` onto a panel whose plot area starts `top` pixels down.
+struct Panel {
+    top: f64,
+    bound: usize,
+}
+
+impl Panel {
+    fn x(&self, value: usize) -> f64 {
+        MARGIN + value as f64 / self.bound as f64 * WIDTH
+    }
+
+    fn y(&self, value: usize) -> f64 {
+        self.top + HEIGHT - value as f64 / self.bound as f64 * HEIGHT
+    }
+
+    fn frame(&self, name: &str) -> String {
+        format!(
+            "<text x=\"{MARGIN}\" y=\"{:.1}\">{name} (0..{})</text>\n\
+             <rect x=\"{MARGIN}\" y=\"{:.1}\" width=\"{WIDTH}\" height=\"{HEIGHT}\" fill=\"none\" stroke=\"#888\"/>\n",
+            self.top - 8.0,
+            self.bound,
+            self.top
+        )
+    }
+
+    fn shade(&self, range: &Range<usize>) -> String {
+        format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{HEIGHT}\" fill=\"#2a2\" fill-opacity=\"0.2\"/>\n",
+            self.x(range.start),
+            self.top,
+            (self.x(range.end) - self.x(range.start)).max(1.0)
+        )
+    }
+
+    fn line(&self, piece: &Piece) -> String {
+        let colour = if piece.from.start == piece.to { "#888" } else { "#22c" };
+        format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{colour}\"/>\n",
+            self.x(piece.from.start),
+            self.y(piece.to),
+            self.x(piece.from.end),
+            self.y(piece.end())
+        )
+    }
+
+    fn mark(&self, seed: usize, location: usize) -> String {
+        format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"#c22\"/>\n\
+             <text x=\"{:.1}\" y=\"{:.1}\" fill=\"#c22\">seed {seed} -> location {location}</text>\n",
+            self.x(seed),
+            self.y(location),
+            self.x(seed) + 6.0,
+            self.y(location) - 6.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn pieces_test() {
+        let mut map = Map::new();
+        map.entry(100, 10, 10).entry(0, 15, 15);
+
+        let result = pieces(&map, 0..40);
+
+        let expected = vec![
+            Piece { from: 0..10, to: 0 },
+            Piece { from: 10..20, to: 100 },
+            Piece { from: 20..30, to: 5 },
+            Piece { from: 30..40, to: 30 },
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn composed_pieces_test() {
+        let almanac = Almanac::from_str(crate::tests::DEFAULT_INPUT.trim());
+
+        let result = composed_pieces(&almanac, 0..120);
+
+        assert_eq!(result.iter().map(|piece| piece.from.len()).sum::<usize>(), 120);
+        for piece in &result {
+            for (i, seed) in piece.from.clone().enumerate() {
+                assert_eq!(piece.to + i, almanac.get_location(&seed));
+            }
+        }
+    }
+
+    #[test]
+    pub fn plot_test() {
+        let almanac = Almanac::from_str(crate::tests::DEFAULT_INPUT.trim());
+
+        let result = plot(&almanac, Some(46));
+
+        assert!(result.starts_with("<svg") && result.ends_with("</svg>\n"));
+        assert_eq!(result.matches("<rect").count(), 8 + 2 * 2);
+        assert!(result.contains("seed 82 -> location 46"));
+    }
+}
@@ -0,0 +1,55 @@
+use std::ops::Range;
+
+/// Sorts `ranges` and merges any that overlap or touch, dropping empty ones.
+pub fn merge(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+pub fn intersect(a: &Range<usize>, b: &Range<usize>) -> Option<Range<usize>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (!range.is_empty()).then_some(range)
+}
+
+/// The parts of `range` not covered by any of `cuts`.
+pub fn subtract<'a>(range: Range<usize>, cuts: impl IntoIterator<Item = &'a Range<usize>>) -> Vec<Range<usize>> {
+    let mut remaining = vec![range];
+    for cut in cuts {
+        remaining = remaining
+            .into_iter()
+            .flat_map(|range| [range.start..range.end.min(cut.start), range.start.max(cut.end)..range.end])
+            .filter(|range| !range.is_empty())
+            .collect();
+    }
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn merge_test() {
+        let result = merge(vec![10..20, 0..5, 5..7, 15..25, 30..30]);
+
+        assert_eq!(result, vec![0..7, 10..25]);
+    }
+
+    #[test]
+    pub fn subtract_test() {
+        let result = subtract(0..100, &[10..20, 50..150, 0..5]);
+
+        assert_eq!(result, vec![5..10, 20..50]);
+        assert_eq!(intersect(&(0..10), &(5..20)), Some(5..10));
+        assert_eq!(intersect(&(0..10), &(10..20)), None);
+    }
+}
@@ -0,0 +1,215 @@
+use std::{fmt, ops::Range};
+
+use crate::ranges;
+
+/// Something about an almanac that parses but makes it ambiguous or suspicious.
+///
+/// `line` is an index starting at 0; `Display` shows it starting at 1. Findings about a whole map
+/// point at its header line.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Finding {
+    /// The seed line has an odd number of values, so it cannot be read as `start length` pairs.
+    OddSeedCount { line: usize, count: usize },
+    /// A line inside a map that is not three numbers.
+    Unparseable { line: usize, text: String },
+    /// An entry whose source or destination range runs past `usize::MAX`.
+    Overflow { line: usize, text: String },
+    /// Two entries claim the same source values. The earlier one wins.
+    Overlap { line: usize, map: String, first_line: usize, range: Range<usize> },
+    /// Two entries map onto the same destination values, so the map is not injective.
+    Collision { line: usize, map: String, first_line: usize, range: Range<usize> },
+    /// An entry maps onto values that are also not covered by any entry, and so map to themselves.
+    IdentityCollision { line: usize, map: String, range: Range<usize> },
+    /// An entry whose destination is its source.
+    Identity { line: usize, map: String, range: Range<usize> },
+    /// Values between the map's source ranges that no entry covers.
+    Gap { line: usize, map: String, range: Range<usize> },
+}
+
+impl Finding {
+    pub fn line(&self) -> usize {
+        match self {
+            Self::OddSeedCount { line, .. }
+            | Self::Unparseable { line, .. }
+            | Self::Overflow { line, .. }
+            | Self::Overlap { line, .. }
+            | Self::Collision { line, .. }
+            | Self::IdentityCollision { line, .. }
+            | Self::Identity { line, .. }
+            | Self::Gap { line, .. } => *line,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line() + 1)?;
+        match self {
+            Self::OddSeedCount { count, .. } => write!(f, "{count} seed values do not make whole pairs"),
+            Self::Unparseable { text, .. } => write!(f, "expected `<destination> <source> <length>`, found `{text}`"),
+            Self::Overflow { text, .. } => write!(f, "`{text}` runs past {}", usize::MAX),
+            Self::Overlap { map, first_line, range, .. } => write!(
+                f,
+                "{map}: sources {range:?} are already mapped on line {}",
+                first_line + 1
+            ),
+            Self::Collision { map, first_line, range, .. } => write!(
+                f,
+                "{map}: destinations {range:?} are also mapped to on line {}",
+                first_line + 1
+            ),
+            Self::IdentityCollision { map, range, .. } => {
+                write!(f, "{map}: destinations {range:?} are also reached by unmapped values")
+            }
+            Self::Identity { map, range, .. } => write!(f, "{map}: {range:?} maps to itself"),
+            Self::Gap { map, range, .. } => write!(f, "{map}: {range:?} is not covered and maps to itself"),
+        }
+    }
+}
+
+struct Entry {
+    line: usize,
+    from: Range<usize>,
+    to: Range<usize>,
+}
+
+/// Reads `input` line by line and reports every finding, in the order of the lines they are on.
+pub fn check(input: &str) -> Vec<Finding> {
+    let mut findings = vec![];
+    let mut maps: Vec<(usize, String, Vec<Entry>)> = vec![];
+
+    for (line, text) in input.lines().enumerate() {
+        if let Some(seeds) = text.strip_prefix("seeds:") {
+            let count = seeds.split_whitespace().count();
+            if count % 2 != 0 {
+                findings.push(Finding::OddSeedCount { line, count });
+            }
+        } else if let Some(name) = text.strip_suffix(':') {
+            maps.push((line, name.trim_end_matches(" map").to_string(), vec![]));
+        } else if let Some((_, _, entries)) = maps.last_mut().filter(|_| !text.trim().is_empty()) {
+            let nums: Vec<usize> = text.split_whitespace().filter_map(|num| num.parse().ok()).collect();
+            match nums[..] {
+                [dest, source, len] if text.split_whitespace().count() == 3 => {
+                    match (source.checked_add(len), dest.checked_add(len)) {
+                        (Some(source_end), Some(dest_end)) => entries.push(Entry {
+                            line,
+                            from: source..source_end,
+                            to: dest..dest_end,
+                        }),
+                        _ => findings.push(Finding::Overflow { line, text: text.to_string() }),
+                    }
+                }
+                _ => findings.push(Finding::Unparseable { line, text: text.to_string() }),
+            }
+        }
+    }
+
+    for (header, map, entries) in &maps {
+        findings.extend(check_map(*header, map, entries));
+    }
+    findings.sort_by_key(Finding::line);
+    findings
+}
+
+fn check_map(header: usize, map: &str, entries: &[Entry]) -> Vec<Finding> {
+    let mut findings = vec![];
+    let map = map.to_string();
+    let sources: Vec<_> = entries.iter().map(|entry| entry.from.clone()).collect();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let line = entry.line;
+        if entry.from == entry.to && !entry.from.is_empty() {
+            findings.push(Finding::Identity { line, map: map.clone(), range: entry.from.clone() });
+        }
+        for earlier in &entries[..i] {
+            if let Some(range) = ranges::intersect(&entry.from, &earlier.from) {
+                findings.push(Finding::Overlap { line, map: map.clone(), first_line: earlier.line, range });
+            }
+            if let Some(range) = ranges::intersect(&entry.to, &earlier.to) {
+                findings.push(Finding::Collision { line, map: map.clone(), first_line: earlier.line, range });
+            }
+        }
+        if entry.from != entry.to {
+            for range in ranges::subtract(entry.to.clone(), &sources) {
+                findings.push(Finding::IdentityCollision { line, map: map.clone(), range });
+            }
+        }
+    }
+
+    let covered = ranges::merge(sources);
+    for pair in covered.windows(2) {
+        findings.push(Finding::Gap { line: header, map: map.clone(), range: pair[0].end..pair[1].start });
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn check_test() {
+        let input = "
+seeds: 79 14 55
+
+seed-to-soil map:
+100 10 10
+0 15 15
+40 40 5
+105 50 x
+
+soil-to-fertilizer map:
+50 98 2
+52 50 48";
+
+        let result = check(input.trim());
+
+        let map = || "seed-to-soil".to_string();
+        let expected = vec![
+            Finding::OddSeedCount { line: 0, count: 3 },
+            Finding::Gap { line: 2, map: map(), range: 30..40 },
+            Finding::IdentityCollision { line: 3, map: map(), range: 100..110 },
+            Finding::Overlap { line: 4, map: map(), first_line: 3, range: 15..20 },
+            Finding::IdentityCollision { line: 4, map: map(), range: 0..10 },
+            Finding::Identity { line: 5, map: map(), range: 40..45 },
+            Finding::Unparseable { line: 6, text: "105 50 x".to_string() },
+        ];
+        assert_eq!(result, expected);
+        assert_eq!(result[3].to_string(), "line 5: seed-to-soil: sources 15..20 are already mapped on line 4");
+    }
+
+    #[test]
+    pub fn check_collision_test() {
+        let input = "
+seeds: 1 2
+
+light-to-temperature map:
+10 0 10
+5 10 10";
+
+        let result = check(input.trim());
+
+        let expected = vec![Finding::Collision {
+            line: 4,
+            map: "light-to-temperature".to_string(),
+            first_line: 3,
+            range: 10..15,
+        }];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn check_overflow_test() {
+        let input = "
+seeds: 1 2
+
+seed-to-soil map:
+0 18446744073709551615 2";
+
+        let result = check(input.trim());
+
+        let expected = vec![Finding::Overflow { line: 3, text: "0 18446744073709551615 2".to_string() }];
+        assert_eq!(result, expected);
+        assert_eq!(result[0].to_string(), "line 4: `0 18446744073709551615 2` runs past 18446744073709551615");
+    }
+}
@@ -0,0 +1,142 @@
+use std::{fmt, ops::RangeInclusive};
+
+use crate::Race;
+
+/// How hard a race is to win.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Analysis {
+    /// The hold times that beat the record, or `None` if no hold time does.
+    pub holds: Option<RangeInclusive<usize>>,
+    /// The hold times that go furthest: one, or two if the race time is odd.
+    pub optimal: Vec<usize>,
+    pub max_distance: usize,
+    /// How far past the record the best hold time goes, negative if it falls short.
+    pub margin: isize,
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.holds {
+            Some(holds) => write!(f, "win holding {}..={}", holds.start(), holds.end())?,
+            None => write!(f, "no winning hold")?,
+        }
+        let optimal: Vec<String> = self.optimal.iter().map(usize::to_string).collect();
+        write!(
+            f,
+            ", best hold {} reaches {} (margin {})",
+            optimal.join(" or "),
+            self.max_distance,
+            self.margin
+        )
+    }
+}
+
+impl Race {
+    /// How far the boat goes when the button is held for `hold`.
+    pub fn distance_for(&self, hold: usize) -> usize {
+        hold * self.time.saturating_sub(hold)
+    }
+
+    /// The hold times that beat the record.
+    ///
+    /// Distance rises up to half the race time and falls symmetrically after it, so the first
+    /// winning hold is found by binary search and the last mirrors it.
+    pub fn winning_holds(&self) -> Option<RangeInclusive<usize>> {
+        let half = self.time / 2;
+        if self.distance_for(half) <= self.distance {
+            return None;
+        }
+        let (mut low, mut high) = (0, half);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.distance_for(mid) > self.distance {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Some(low..=self.time - low)
+    }
+
+    pub fn analyse(&self) -> Analysis {
+        let mut optimal = vec![self.time / 2];
+        if self.time % 2 == 1 {
+            optimal.push(self.time / 2 + 1);
+        }
+        let max_distance = self.distance_for(self.time / 2);
+
+        Analysis {
+            holds: self.winning_holds(),
+            optimal,
+            max_distance,
+            margin: max_distance as isize - self.distance as isize,
+        }
+    }
+
+    /// `(hold, distance)` for `samples` hold times spread evenly over `0..=time`, ends included
+    /// when there are at least two samples. Every hold time is used if there are fewer of them
+    /// than samples.
+    pub fn distance_curve(&self, samples: usize) -> Vec<(usize, usize)> {
+        let holds: Vec<usize> = match samples {
+            0 => vec![],
+            1 => vec![self.time / 2],
+            _ if samples > self.time => (0..=self.time).collect(),
+            _ => (0..samples).map(|i| i * self.time / (samples - 1)).collect(),
+        };
+        holds.into_iter().map(|hold| (hold, self.distance_for(hold))).collect()
+    }
+}
+
+/// One row per sampled hold time of every race, numbered from 1.
+pub fn curves_to_csv(races: &[Race], samples: usize) -> String {
+    let mut csv = String::from("race,hold,distance,wins\n");
+    for (i, race) in races.iter().enumerate() {
+        for (hold, distance) in race.distance_curve(samples) {
+            csv.push_str(&format!("{},{hold},{distance},{}\n", i + 1, distance > race.distance));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn analyse_test() {
+        let races = [
+            Race { time: 7, distance: 9 },
+            Race { time: 30, distance: 200 },
+            Race { time: 4, distance: 4 },
+        ];
+
+        let result: Vec<Analysis> = races.iter().map(Race::analyse).collect();
+
+        let expected = vec![
+            Analysis { holds: Some(2..=5), optimal: vec![3, 4], max_distance: 12, margin: 3 },
+            Analysis { holds: Some(11..=19), optimal: vec![15], max_distance: 225, margin: 25 },
+            Analysis { holds: None, optimal: vec![2], max_distance: 4, margin: 0 },
+        ];
+        assert_eq!(result, expected);
+        assert_eq!(result[0].to_string(), "win holding 2..=5, best hold 3 or 4 reaches 12 (margin 3)");
+    }
+
+    #[test]
+    pub fn curves_to_csv_test() {
+        let races = [Race { time: 7, distance: 9 }, Race { time: 2, distance: 0 }];
+
+        let result = curves_to_csv(&races, 3);
+
+        assert_eq!(races[0].distance_curve(0), vec![]);
+        assert_eq!(races[0].distance_curve(1), vec![(3, 12)]);
+        assert_eq!(Race { time: 0, distance: 0 }.distance_curve(2), vec![(0, 0)]);
+        assert_eq!(result, "race,hold,distance,wins
+1,0,0,false
+1,3,12,true
+1,7,0,false
+2,0,0,false
+2,1,1,true
+2,2,0,false
+");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::Race;
+
+/// How far a boat goes for a given hold time.
+///
+/// Distances are `f64` so models with friction fit the same trait. The integer models stay exact
+/// as long as distances are below 2^53.
+pub trait BoatModel {
+    /// Distance covered in a race of `time` milliseconds when the button is held for `hold` of them.
+    fn distance(&self, time: usize, hold: usize) -> f64;
+
+    /// The hold time that goes furthest.
+    ///
+    /// Assumes distance rises with the hold time and then falls, and finds where it stops rising.
+    fn best_hold(&self, time: usize) -> usize {
+        let (mut low, mut high) = (0, time);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.distance(time, mid) < self.distance(time, mid + 1) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    /// How many hold times in `0..=race.time` beat the record.
+    ///
+    /// Searches both sides of `best_hold` for the first and last winning hold times, so it relies
+    /// on the same shape of curve.
+    fn win_count(&self, race: &Race) -> usize {
+        let wins = |hold| self.distance(race.time, hold) > race.distance as f64;
+        let best = self.best_hold(race.time);
+        if !wins(best) {
+            return 0;
+        }
+
+        let (mut low, mut high) = (0, best);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if wins(mid) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        let first = low;
+
+        let (mut low, mut high) = (best, race.time);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if wins(mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low - first + 1
+    }
+}
+
+/// The puzzle's model: each millisecond held adds 1 mm/ms of speed, and the boat never slows.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unit;
+
+impl BoatModel for Unit {
+    fn distance(&self, time: usize, hold: usize) -> f64 {
+        (hold * time.saturating_sub(hold)) as f64
+    }
+
+    fn best_hold(&self, time: usize) -> usize {
+        time / 2
+    }
+
+    fn win_count(&self, race: &Race) -> usize {
+        quadratic_win_count(race.time, race.distance)
+    }
+}
+
+/// Each millisecond held adds `.0` mm/ms of speed.
+#[derive(Debug, Clone, Copy)]
+pub struct Acceleration(pub usize);
+
+impl BoatModel for Acceleration {
+    fn distance(&self, time: usize, hold: usize) -> f64 {
+        (self.0 as u128 * hold as u128 * time.saturating_sub(hold) as u128) as f64
+    }
+
+    fn best_hold(&self, time: usize) -> usize {
+        time / 2
+    }
+
+    /// `a * h * (time - h) > record` holds exactly when `h * (time - h) > record / a`, rounded
+    /// down, which is a quadratic in `h`.
+    fn win_count(&self, race: &Race) -> usize {
+        match race.distance.checked_div(self.0) {
+            Some(record) => quadratic_win_count(race.time, record),
+            None => 0,
+        }
+    }
+}
+
+/// Like `Unit`, but speed stops growing after `.0` mm/ms.
+#[derive(Debug, Clone, Copy)]
+pub struct CappedSpeed(pub usize);
+
+impl BoatModel for CappedSpeed {
+    fn distance(&self, time: usize, hold: usize) -> f64 {
+        (hold.min(self.0) * time.saturating_sub(hold)) as f64
+    }
+
+    /// Holding past the cap only loses time, and below it the usual peak at half the race applies.
+    fn best_hold(&self, time: usize) -> usize {
+        self.0.min(time / 2)
+    }
+}
+
+/// Like `Unit`, but once released the boat slows down in proportion to its speed, losing `.0` of
+/// it per millisecond.
+#[derive(Debug, Clone, Copy)]
+pub struct Drag(pub f64);
+
+impl BoatModel for Drag {
+    /// Speed `hold` decaying as `hold * e^(-k t)` covers `hold * (1 - e^(-k t)) / k` in `t`.
+    fn distance(&self, time: usize, hold: usize) -> f64 {
+        let moving = time.saturating_sub(hold) as f64;
+        if self.0 == 0.0 {
+            return hold as f64 * moving;
+        }
+        hold as f64 * -(-self.0 * moving).exp_m1() / self.0
+    }
+}
+
+/// Counts `h` in `0..=time` with `h * (time - h) > record`, from the roots of
+/// `h^2 - time * h + record = 0`.
+fn quadratic_win_count(time: usize, record: usize) -> usize {
+    let (time, record) = (time as u128, record as u128);
+    let Some(discriminant) = (time * time).checked_sub(4 * record) else {
+        return 0;
+    };
+    let wins = |hold: u128| hold * (time - hold) > record;
+
+    // The square root is rounded down, so nudge the first winning hold time into place.
+    let mut first = (time - discriminant.isqrt()) / 2;
+    while first > 0 && wins(first - 1) {
+        first -= 1;
+    }
+    while first <= time / 2 && !wins(first) {
+        first += 1;
+    }
+    if first > time / 2 {
+        return 0;
+    }
+    (time - 2 * first + 1) as usize
+}
+
+/// Parses `unit`, `acceleration:<a>`, `cap:<top speed>` or `drag:<k>`.
+pub fn parse_model(input: &str) -> Result<Box<dyn BoatModel>, String> {
+    let (name, value) = input.split_once(':').unwrap_or((input, ""));
+    let invalid = || format!("invalid value `{value}` for model `{name}`");
+    match name {
+        "unit" => Ok(Box::new(Unit)),
+        "acceleration" => Ok(Box::new(Acceleration(value.parse().map_err(|_| invalid())?))),
+        "cap" => Ok(Box::new(CappedSpeed(value.parse().map_err(|_| invalid())?))),
+        // Negative or infinite drag would break the rise-then-fall shape the solvers rely on.
+        "drag" => match value.parse::<f64>() {
+            Ok(k) if k.is_finite() && k >= 0.0 => Ok(Box::new(Drag(k))),
+            _ => Err(invalid()),
+        },
+        _ => Err(format!("unknown model `{input}`, expected `unit`, `acceleration:<a>`, `cap:<c>` or `drag:<k>`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts by trying every hold time.
+    fn brute_force(model: &dyn BoatModel, race: &Race) -> usize {
+        (0..=race.time)
+            .filter(|&hold| model.distance(race.time, hold) > race.distance as f64)
+            .count()
+    }
+
+    #[test]
+    pub fn models_test() {
+        let race = Race { time: 30, distance: 200 };
+        let models: [&dyn BoatModel; 4] = [&Acceleration(2), &CappedSpeed(12), &Drag(0.01), &Drag(0.0)];
+
+        let result: Vec<usize> = models.iter().map(|model| model.win_count(&race)).collect();
+
+        // Doubling the speed is like beating 100 instead of 200; a cap of 12 leaves 11..=13
+        // winning; drag costs part of the 11..=19 the puzzle's model wins with.
+        let expected = vec![23, 3, 6, 9];
+        assert_eq!(result, expected);
+        for (model, count) in models.iter().zip(&result) {
+            assert_eq!(brute_force(*model, &race), *count);
+        }
+    }
+
+    #[test]
+    pub fn parse_model_test() {
+        let race = Race { time: 30, distance: 200 };
+
+        let result = parse_model("acceleration:3").unwrap().win_count(&race);
+
+        assert_eq!(result, 25);
+        assert!(parse_model("cap:x").is_err());
+        assert!(["drag:-0.1", "drag:NaN", "drag:inf"].iter().all(|model| parse_model(model).is_err()));
+        assert!(parse_model("drag:0").is_ok());
+        assert_eq!(Acceleration(usize::MAX).distance(4, 2), usize::MAX as f64 * 4.0);
+        assert!(parse_model("warp").is_err());
+    }
+
+    #[test]
+    pub fn solvers_agree_test() {
+        for time in 0..=40 {
+            for distance in 0..=time * time / 4 + 1 {
+                let race = Race { time, distance };
+                let expected = brute_force(&Unit, &race);
+
+                let result = [
+                    race.possible_win_count(),
+                    Unit.win_count(&race),
+                    Acceleration(1).win_count(&race),
+                    CappedSpeed(usize::MAX).win_count(&race),
+                    Drag(0.0).win_count(&race),
+                    race.winning_holds().map_or(0, |holds| holds.count()),
+                ];
+
+                assert_eq!(result, [expected; 6], "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    pub fn models_agree_test() {
+        for time in 0..=30 {
+            for distance in (0..=time * time).step_by(7) {
+                let race = Race { time, distance };
+                let models: [&dyn BoatModel; 7] = [
+                    &Acceleration(0),
+                    &Acceleration(3),
+                    &CappedSpeed(0),
+                    &CappedSpeed(1),
+                    &CappedSpeed(time / 3),
+                    &Drag(0.01),
+                    &Drag(0.2),
+                ];
+
+                for model in models {
+                    assert_eq!(model.win_count(&race), brute_force(model, &race), "{race:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn win_count_edge_test() {
+        let races = [
+            Race { time: 0, distance: 0 },
+            Race { time: 1, distance: 0 },
+            Race { time: 2, distance: 0 },
+            Race { time: 3, distance: 1 },
+        ];
+
+        let result: Vec<usize> = races.iter().map(Race::possible_win_count).collect();
+
+        // Holding for `time - 1` wins the last two races.
+        let expected = vec![0, 0, 1, 2];
+        assert_eq!(result, expected);
+    }
+}
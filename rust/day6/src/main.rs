@@ -1,12 +1,31 @@
-use std::fs;
+use std::{env, fs};
+
+use boat::BoatModel;
+
+mod analytics;
+mod boat;
 
 fn main() {
+    let Args { analyse, curve, model } = parse_args(env::args().skip(1)).unwrap();
     let input = get_input();
 
-    let part_1_result = part_1(&input);
+    if analyse {
+        for (i, race) in parse_input_part_1(&input).iter().enumerate() {
+            println!("Race {}: {}", i + 1, race.analyse());
+        }
+        println!("Single race: {}", parse_input_part_2(&input).analyse());
+        return;
+    }
+
+    if let Some(samples) = curve {
+        print!("{}", analytics::curves_to_csv(&parse_input_part_1(&input), samples));
+        return;
+    }
+
+    let part_1_result = part_1(&input, model.as_ref());
     println!("Part 1 result: {part_1_result}");
 
-    let part_2_result = part_2(&input);
+    let part_2_result = part_2(&input, model.as_ref());
     println!("Part 2 result: {part_2_result}");
 }
 
@@ -14,9 +33,40 @@ fn get_input() -> String {
     String::from_utf8(fs::read("input").unwrap()).unwrap()
 }
 
-fn part_1(input: &str) -> usize {
+struct Args {
+    analyse: bool,
+    curve: Option<usize>,
+    model: Box<dyn BoatModel>,
+}
+
+/// `[--model unit|acceleration:<a>|cap:<c>|drag:<k> | --analyse | --curve <samples per race>]`
+///
+/// The analytics only know the puzzle's model, so `--model` cannot be combined with them.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut analyse = false;
+    let mut curve = None;
+    let mut model: Option<Box<dyn BoatModel>> = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--analyse" => analyse = true,
+            "--curve" => {
+                let samples = args.next().ok_or("--curve needs a sample count")?;
+                curve = Some(samples.parse().map_err(|_| format!("invalid sample count `{samples}`"))?);
+            }
+            "--model" => model = Some(boat::parse_model(&args.next().ok_or("--model needs a value")?)?),
+            _ => return Err(format!("unknown argument `{arg}`")),
+        }
+    }
+    if model.is_some() && (analyse || curve.is_some()) {
+        return Err("--model cannot be combined with --analyse or --curve".to_string());
+    }
+    let model = model.unwrap_or_else(|| Box::new(boat::Unit));
+    Ok(Args { analyse, curve, model })
+}
+
+fn part_1(input: &str, model: &dyn BoatModel) -> usize {
     let races = parse_input_part_1(input);
-    races.iter().map(|race| race.possible_win_count()).product()
+    races.iter().map(|race| model.win_count(race)).product()
 }
 
 fn parse_input_part_1(input: &str) -> Vec<Race> {
@@ -25,14 +75,14 @@ fn parse_input_part_1(input: &str) -> Vec<Race> {
         values
             .trim()
             .split(' ')
-            .filter(|value| value.trim().len() > 0)
+            .filter(|value| !value.trim().is_empty())
             .map(|value| value.trim().parse().unwrap())
     });
-    let mut times = lines.next().unwrap();
+    let times = lines.next().unwrap();
     let mut distance = lines.next().unwrap();
 
     let mut races = vec![];
-    while let Some(time) = times.next() {
+    for time in times {
         let distance = distance
             .next()
             .expect("time and distance size should always be the same");
@@ -43,9 +93,9 @@ fn parse_input_part_1(input: &str) -> Vec<Race> {
     races
 }
 
-fn part_2(input: &str) -> usize {
+fn part_2(input: &str, model: &dyn BoatModel) -> usize {
     let race = parse_input_part_2(input);
-    race.possible_win_count()
+    model.win_count(&race)
 }
 
 fn parse_input_part_2(input: &str) -> Race {
@@ -74,8 +124,11 @@ struct Race {
 }
 
 impl Race {
+    /// Tries every hold time, including none and all of it. The faster solvers are checked
+    /// against this one.
+    #[cfg(test)]
     pub fn possible_win_count(&self) -> usize {
-        (1..self.time - 1)
+        (0..=self.time)
             .filter(|time| {
                 let time_left = self.time - time;
                 time * time_left > self.distance
@@ -94,7 +147,7 @@ mod test {
 Time:      7  15   30
 Distance:  9  40  200";
 
-        let result = part_1(input.trim());
+        let result = part_1(input.trim(), &boat::Unit);
 
         let expect = 288;
         assert_eq!(result, expect);
@@ -106,7 +159,7 @@ Distance:  9  40  200";
 Time:      7  15   30
 Distance:  9  40  200";
 
-        let result = part_2(input.trim());
+        let result = part_2(input.trim(), &boat::Unit);
 
         let expected = 71503;
         assert_eq!(result, expected);
@@ -138,6 +191,7 @@ Distance:  9  40  200";
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     pub fn race_possible_win_count_test() {
         let races = vec![
             Race {
@@ -163,4 +217,17 @@ Distance:  9  40  200";
         let expected = vec![4, 8, 9];
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn parse_args_test() {
+        let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+        let result = args(&["--model", "cap:12"]).unwrap();
+
+        assert_eq!(result.model.win_count(&Race { time: 30, distance: 200 }), 3);
+        assert!(args(&["--analyse"]).is_ok());
+        let combined = Some("--model cannot be combined with --analyse or --curve".to_string());
+        assert_eq!(args(&["--model", "unit", "--analyse"]).err(), combined);
+        assert_eq!(args(&["--curve", "5", "--model", "drag:0.1"]).err(), combined);
+    }
 }
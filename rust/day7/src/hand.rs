@@ -1,6 +1,8 @@
-use std::{cmp::Ordering, fmt::Debug, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, fmt};
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone)]
+pub const HAND_SIZE: usize = 5;
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
 pub enum Card {
     Ace = 14,
     King = 13,
@@ -18,7 +20,98 @@ pub enum Card {
     Joker = 1,
 }
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+/// Reads `J` as a jack; use `Ruleset::Jokers` to read it as a joker.
+impl TryFrom<char> for Card {
+    type Error = ParseHandError;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        match ch {
+            'A' => Ok(Card::Ace),
+            'K' => Ok(Card::King),
+            'Q' => Ok(Card::Queen),
+            'J' => Ok(Card::Jack),
+            'T' => Ok(Card::Ten),
+            '9' => Ok(Card::Nine),
+            '8' => Ok(Card::Eight),
+            '7' => Ok(Card::Seven),
+            '6' => Ok(Card::Six),
+            '5' => Ok(Card::Five),
+            '4' => Ok(Card::Four),
+            '3' => Ok(Card::Three),
+            '2' => Ok(Card::Two),
+            _ => Err(ParseHandError::InvalidCard(ch)),
+        }
+    }
+}
+
+impl From<Card> for char {
+    fn from(card: Card) -> Self {
+        match card {
+            Card::Ace => 'A',
+            Card::King => 'K',
+            Card::Queen => 'Q',
+            Card::Jack | Card::Joker => 'J',
+            Card::Ten => 'T',
+            Card::Nine => '9',
+            Card::Eight => '8',
+            Card::Seven => '7',
+            Card::Six => '6',
+            Card::Five => '5',
+            Card::Four => '4',
+            Card::Three => '3',
+            Card::Two => '2',
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+/// What `J` stands for in a hand.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum Ruleset {
+    /// A jack, ranked between ten and queen.
+    #[default]
+    Jacks,
+    /// A joker, ranked lowest but standing in for whatever card makes the best hand type.
+    Jokers,
+}
+
+impl Ruleset {
+    pub fn card(&self, ch: char) -> Result<Card, ParseHandError> {
+        match (self, Card::try_from(ch)?) {
+            (Ruleset::Jokers, Card::Jack) => Ok(Card::Joker),
+            (_, card) => Ok(card),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseHandError {
+    InvalidCard(char),
+    WrongLength(usize),
+    /// A line of input without a bet after the hand.
+    MissingBet(String),
+    InvalidBet(String),
+}
+
+impl fmt::Display for ParseHandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCard(ch) => write!(f, "`{ch}` is not a card"),
+            Self::WrongLength(len) => write!(f, "a hand has {HAND_SIZE} cards, not {len}"),
+            Self::MissingBet(line) => write!(f, "expected `<hand> <bet>`, found `{line}`"),
+            Self::InvalidBet(bet) => write!(f, "invalid bet `{bet}`"),
+        }
+    }
+}
+
+impl std::error::Error for ParseHandError {}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
 pub enum HandType {
     HighCard = 1,
     OnePair,
@@ -29,7 +122,7 @@ pub enum HandType {
     FiveOfAKind,
 }
 
-#[derive(Debug, Eq, PartialEq, Ord)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Hand {
     pub cards: Vec<Card>,
     pub hand_type: HandType,
@@ -44,56 +137,31 @@ impl Hand {
         }
     }
 
-    pub fn from_str(input: &str) -> Self {
-        let cards = input.chars().map(|ch| match ch {
-            'A' => Card::Ace,
-            'K' => Card::King,
-            'Q' => Card::Queen,
-            'J' => Card::Jack,
-            'T' => Card::Ten,
-            '9' => Card::Nine,
-            '8' => Card::Eight,
-            '7' => Card::Seven,
-            '6' => Card::Six,
-            '5' => Card::Five,
-            '4' => Card::Four,
-            '3' => Card::Three,
-            '2' => Card::Two,
-            _ => panic!("Cannot map {ch}")
-        }).collect();
-        let hand_type = Self::get_hand_type(&cards);
-        Self { cards, hand_type }
-    }
-
-    pub fn from_str_with_jokers(input: &str) -> Self {
+    pub fn parse(input: &str, ruleset: Ruleset) -> Result<Self, ParseHandError> {
         let cards = input
             .chars()
-            .map(|ch| match ch {
-                'A' => Card::Ace,
-                'K' => Card::King,
-                'Q' => Card::Queen,
-                'J' => Card::Joker,
-                'T' => Card::Ten,
-                '9' => Card::Nine,
-                '8' => Card::Eight,
-                '7' => Card::Seven,
-                '6' => Card::Six,
-                '5' => Card::Five,
-                '4' => Card::Four,
-                '3' => Card::Three,
-                '2' => Card::Two,
-                _ => panic!("Cannot map {ch}")
-            })
-            .collect();
-        let hand_type = Self::get_hand_type(&cards);
-        Self { cards, hand_type }
+            .map(|ch| ruleset.card(ch))
+            .collect::<Result<Vec<_>, _>>()?;
+        if cards.len() != HAND_SIZE {
+            return Err(ParseHandError::WrongLength(cards.len()));
+        }
+        Ok(Self::with_cards(cards))
     }
 
-    fn get_hand_type(cards: &Vec<Card>) -> HandType {
+    /// A number that orders hands the same way `Ord` does: the hand type in bits 20 and up, then
+    /// each card's rank in a nibble, first card highest.
+    pub fn key(&self) -> u32 {
+        debug_assert_eq!(self.cards.len(), HAND_SIZE, "keys only order hands of {HAND_SIZE} cards");
+        self.cards
+            .iter()
+            .fold(self.hand_type as u32, |key, &card| key << 4 | card as u32)
+    }
+
+    fn get_hand_type(cards: &[Card]) -> HandType {
         let card_counts = cards
             .iter()
             .fold(HashMap::new(), |mut map: HashMap<Card, usize>, card| {
-                map.entry(card.clone()).and_modify(|entry| *entry += 1).or_insert(1);
+                map.entry(*card).and_modify(|entry| *entry += 1).or_insert(1);
                 map
             });
 
@@ -153,36 +221,34 @@ impl Hand {
     }
 }
 
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.cards.cmp(&other.cards))
+    }
+}
+
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.hand_type.cmp(&other.hand_type) {
-            Ordering::Less => return Some(Ordering::Less),
-            Ordering::Greater => return Some(Ordering::Greater),
-            Ordering::Equal => {
-                for i in 0..self.cards.len() {
-                    let card = self.cards.get(i).unwrap();
-                    let other_card = other.cards.get(i).unwrap();
-
-                    match card.cmp(other_card) {
-                        Ordering::Equal => continue,
-                        Ordering::Less => return Some(Ordering::Less),
-                        Ordering::Greater => return Some(Ordering::Greater),
-                    };
-                }
+        Some(self.cmp(other))
+    }
+}
 
-                Some(Ordering::Equal)
-            }
-        }
+/// Prints the cards as they were written, with jokers as `J`.
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cards.iter().try_for_each(|card| write!(f, "{card}"))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Hand, Card, HandType};
+    use super::{Card, Hand, HandType, ParseHandError, Ruleset};
 
     #[test]
     pub fn hand_from_str() {
-        let inputs = vec![
+        let inputs = [
             "32T3K",
             "T55J5",
             "KK677",
@@ -192,7 +258,7 @@ mod test {
 
         let results: Vec<_> = inputs
             .iter()
-            .map(|input| Hand::from_str(input))
+            .map(|input| Hand::parse(input, Ruleset::Jacks).unwrap())
             .collect();
 
         let expected = vec![
@@ -233,4 +299,70 @@ mod test {
         let expected = HandType::FiveOfAKind;
         assert_eq!(hand.hand_type, expected);
     }
+
+    #[test]
+    pub fn hand_parse_with_jokers() {
+        let result = Hand::parse("KTJJT", Ruleset::Jokers).unwrap();
+
+        let expected = Hand::with_cards(vec![Card::King, Card::Ten, Card::Joker, Card::Joker, Card::Ten]);
+        assert_eq!(result, expected);
+        assert_eq!(result.hand_type, HandType::FourOfAKind);
+        assert_eq!(result.to_string(), "KTJJT");
+    }
+
+    #[test]
+    pub fn hand_parse_errors() {
+        let inputs = ["32T3X", "32T3", "32T3KK", ""];
+
+        let results: Vec<_> = inputs
+            .iter()
+            .map(|input| Hand::parse(input, Ruleset::Jacks).unwrap_err())
+            .collect();
+
+        let expected = vec![
+            ParseHandError::InvalidCard('X'),
+            ParseHandError::WrongLength(4),
+            ParseHandError::WrongLength(6),
+            ParseHandError::WrongLength(0),
+        ];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    pub fn card_char_round_trip() {
+        let chars = "AKQJT98765432";
+
+        let results: String = chars
+            .chars()
+            .map(|ch| Card::try_from(ch).unwrap().to_string())
+            .collect();
+
+        assert_eq!(results, chars);
+        assert_eq!(char::from(Card::Joker), 'J');
+        assert_eq!(Card::try_from('1'), Err(ParseHandError::InvalidCard('1')));
+    }
+
+    #[test]
+    pub fn key_order_matches_hand_order() {
+        // A small linear congruential generator picks the hands, so no dependency is needed.
+        let mut state: u64 = 7;
+        let mut next_char = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            "AKQJT98765432".chars().nth((state >> 33) as usize % 13).unwrap()
+        };
+        let inputs: Vec<String> = (0..400)
+            .map(|_| (0..5).map(|_| next_char()).collect())
+            .chain(["JJJJJ", "AAAAA", "22222", "2345J", "J2345"].map(String::from))
+            .collect();
+
+        for ruleset in [Ruleset::Jacks, Ruleset::Jokers] {
+            let hands: Vec<Hand> = inputs.iter().map(|input| Hand::parse(input, ruleset).unwrap()).collect();
+
+            for a in &hands {
+                for b in &hands {
+                    assert_eq!(a.key().cmp(&b.key()), a.cmp(b), "{a} against {b}");
+                }
+            }
+        }
+    }
 }
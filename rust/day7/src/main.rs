@@ -1,78 +1,71 @@
-use std::fs;
+use std::{fs, process};
 
-use hand::Hand;
+use hand::{Hand, ParseHandError, Ruleset};
 
 mod hand;
 
 fn main() {
     let input = get_input();
 
-    let part_1_result = part_1(&input);
+    if let Err(error) = run(&input) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+fn run(input: &str) -> Result<(), ParseHandError> {
+    let part_1_result = part_1(input)?;
     println!("Part 1 result: {part_1_result}");
 
-    let part_2_result = part_2(&input);
+    let part_2_result = part_2(input)?;
     println!("Part 2 result: {part_2_result}");
+    Ok(())
 }
 
 fn get_input() -> String {
     String::from_utf8(fs::read("input").unwrap()).unwrap()
 }
 
-pub fn part_1(input: &str) -> usize {
-    let (hands, bets) = parse_input_part_1(input);
-    let mut hands: Vec<_> = hands.iter().enumerate().collect();
-    hands.sort_by_key(|(_, hand)| *hand);
+pub fn part_1(input: &str) -> Result<usize, ParseHandError> {
+    let (hands, bets) = parse_input(input, Ruleset::Jacks)?;
+    Ok(total_winnings(&hands, &bets))
+}
+
+/// Ranks the hands by their keys and adds up each bet times its rank. Equal hands keep their
+/// input order.
+fn total_winnings(hands: &[Hand], bets: &[usize]) -> usize {
+    let mut ranked: Vec<(u32, usize)> = hands.iter().map(Hand::key).zip(bets.iter().copied()).collect();
+    ranked.sort_by_key(|&(key, _)| key);
 
-    hands
+    ranked
         .iter()
         .enumerate()
-        .fold(0, |acc, (i, (bet_index, _))| acc + (bets.get(*bet_index).unwrap() * (i + 1)))
+        .fold(0, |acc, (i, (_, bet))| acc + bet * (i + 1))
 }
 
-fn parse_input_part_1(input: &str) -> (Vec<Hand>, Vec<usize>) {
+fn parse_input(input: &str, ruleset: Ruleset) -> Result<(Vec<Hand>, Vec<usize>), ParseHandError> {
     input
         .lines()
         .map(|line| {
-            let (hand, bet) = line.split_once(' ').unwrap();
+            let (hand, bet) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseHandError::MissingBet(line.to_string()))?;
 
-            let hand = Hand::from_str(hand);
-            (hand, bet.parse().unwrap())
+            let hand = Hand::parse(hand, ruleset)?;
+            let bet = bet.parse().map_err(|_| ParseHandError::InvalidBet(bet.to_string()))?;
+            Ok((hand, bet))
         })
-        .fold((vec![], vec![]), |(mut hands, mut bets), (hand, bet)| {
+        .try_fold((vec![], vec![]), |(mut hands, mut bets), line| {
+            let (hand, bet) = line?;
             hands.push(hand);
             bets.push(bet);
-            (hands, bets)
+            Ok((hands, bets))
         })
 }
 
-pub fn part_2(input: &str) -> usize {
-    let (hands, bets) = parse_input_part_2(input);
-    let mut hands: Vec<_> = hands
-        .iter()
-        .enumerate()
-        .collect();
-    hands.sort_by_key(|(_, hand)| *hand);
-
-    hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (i, (bet_index, _))| acc + (bets.get(*bet_index).unwrap() * (i + 1)))
-}
-
-fn parse_input_part_2(input: &str) -> (Vec<Hand>, Vec<usize>) {
-    input
-        .lines()
-        .map(|line| {
-            let (hand, bet) = line.split_once(' ').unwrap();
-
-            let hand = Hand::from_str_with_jokers(hand);
-            (hand, bet.parse().unwrap())
-        })
-    .fold((vec![], vec![]), |(mut hands, mut bets), (hand, bet)| {
-        hands.push(hand);
-        bets.push(bet);
-        (hands, bets)
-    })
+pub fn part_2(input: &str) -> Result<usize, ParseHandError> {
+    let (hands, bets) = parse_input(input, Ruleset::Jokers)?;
+    Ok(total_winnings(&hands, &bets))
 }
 
 #[cfg(test)]
@@ -88,7 +81,7 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
-        let result = part_1(input.trim());
+        let result = part_1(input.trim()).unwrap();
 
         let expected = 6440;
         assert_eq!(result, expected);
@@ -103,9 +96,36 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
-        let result = part_2(input.trim());
+        let result = part_2(input.trim()).unwrap();
 
         let expected = 5905;
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn parse_input_errors_test() {
+        let inputs = ["32T3K 765\nT55J5", "32T3K x65", "32T3 765"];
+
+        let result: Vec<_> = inputs.iter().map(|input| part_1(input).unwrap_err()).collect();
+
+        let expected = vec![
+            ParseHandError::MissingBet("T55J5".to_string()),
+            ParseHandError::InvalidBet("x65".to_string()),
+            ParseHandError::WrongLength(4),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    pub fn equal_hands_keep_input_order_test() {
+        let input = "
+32T3K 100
+32T3K 1
+KK677 5";
+
+        let result = part_1(input.trim()).unwrap();
+
+        let expected = 100 + 2 + 3 * 5;
+        assert_eq!(result, expected);
+    }
 }